@@ -0,0 +1,261 @@
+use crate::cuneiform;
+use crate::floating::FloatingNum;
+use crate::format;
+use crate::value::{parse_transliteration, SexagesimalNum, Value};
+use num_bigint::BigUint;
+use num_traits::{Signed, ToPrimitive};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AtfError {
+    #[error("Could not read ATF file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Line {0}: unknown relation '{1}' (expected sum, product, reciprocal or none)")]
+    UnknownRelation(usize, String),
+}
+
+/// Arithmetic relationship declared for the rows that follow, with a
+/// protocol line such as `#abzu: sum`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    /// The last number is the sum of the others
+    Sum,
+    /// The last number is the product of the others
+    Product,
+    /// Two numbers whose product is a power of 60 (igi / igi-bi)
+    Reciprocal,
+}
+
+/// A text line with the numbers found in its `&`-separated columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericLine {
+    /// Line number in the file, counted from 1
+    pub line: usize,
+    /// ATF line label, such as `1.` or `3'.`
+    pub label: String,
+    pub values: Vec<Value>,
+}
+
+/// A row that does not satisfy its declared relation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    pub line: usize,
+    pub label: String,
+    pub message: String,
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} ({}): possible scribal error: {}", self.line, self.label, self.message)
+    }
+}
+
+/// Picks out the text lines that contain numbers. Each run of number
+/// signs is one number, and `&` column separators end a run; damage
+/// marks such as `[...]`, `#` and `?` are ignored.
+pub fn numeric_lines(source: &str) -> Vec<NumericLine> {
+    source.lines()
+        .enumerate()
+        .filter_map(|(index, line)| numeric_line(index + 1, line))
+        .collect()
+}
+
+fn numeric_line(line: usize, text: &str) -> Option<NumericLine> {
+    let (label, content) = text.trim().split_once(char::is_whitespace)?;
+    if !label.ends_with('.') || label.len() < 2 || label.starts_with(['&', '@', '$', '#', '>']) {
+        return None;
+    }
+    
+    let mut values = Vec::new();
+    for column in content.split('&') {
+        let mut run: Vec<String> = Vec::new();
+        for word in column.split_whitespace() {
+            let cleaned: String = word.chars()
+                .filter(|ch| !matches!(ch, '[' | ']' | '⸢' | '⸣' | '<' | '>' | '#' | '?' | '!' | '*'))
+                .collect();
+            if parse_transliteration(&cleaned).is_ok() {
+                run.push(cleaned);
+            } else if !run.is_empty() {
+                values.extend(parse_transliteration(&run.join(" ")).ok());
+                run.clear();
+            }
+        }
+        if !run.is_empty() {
+            values.extend(parse_transliteration(&run.join(" ")).ok());
+        }
+    }
+    
+    if values.is_empty() {
+        return None;
+    }
+    Some(NumericLine { line, label: label.to_string(), values })
+}
+
+/// Checks every row against the relation declared above it and returns
+/// the rows that disagree
+pub fn check(source: &str) -> Result<Vec<Discrepancy>, AtfError> {
+    let mut relations = Vec::new();
+    let mut relation = None;
+    for (index, text) in source.lines().enumerate() {
+        if let Some(name) = text.trim().strip_prefix("#abzu:") {
+            relation = match name.trim() {
+                "sum" => Some(Relation::Sum),
+                "product" => Some(Relation::Product),
+                "reciprocal" => Some(Relation::Reciprocal),
+                "none" => None,
+                other => return Err(AtfError::UnknownRelation(index + 1, other.to_string())),
+            };
+        }
+        relations.push(relation);
+    }
+    
+    let discrepancies = numeric_lines(source)
+        .into_iter()
+        .filter_map(|row| {
+            let relation = relations[row.line - 1]?;
+            let message = check_row(relation, &row.values)?;
+            Some(Discrepancy { line: row.line, label: row.label, message })
+        })
+        .collect();
+    
+    Ok(discrepancies)
+}
+
+/// Describes how a row breaks `relation`, or None if it holds
+fn check_row(relation: Relation, values: &[Value]) -> Option<String> {
+    let numbers: Vec<SexagesimalNum> = values.iter().filter_map(to_exact).collect();
+    
+    match relation {
+        Relation::Sum | Relation::Product => {
+            let (written, terms) = numbers.split_last()?;
+            if terms.is_empty() {
+                return Some("expected at least two numbers".to_string());
+            }
+            let (symbol, expected) = if relation == Relation::Sum {
+                (" + ", terms.iter().skip(1).try_fold(terms[0].clone(), |total, term| total.checked_add(term)))
+            } else {
+                (" × ", terms.iter().skip(1).try_fold(terms[0].clone(), |total, term| total.checked_mul(term)))
+            };
+            let expected = expected?;
+            
+            // Sums need aligned places, but a product is only known up to a
+            // power of 60, just like the numbers on the tablet
+            let matches = match relation {
+                Relation::Product => floating(&expected) == floating(written),
+                _ => expected == *written,
+            };
+            if matches {
+                return None;
+            }
+            let terms: Vec<String> = terms.iter().map(place_notation).collect();
+            Some(format!(
+                "{} = {}, but the tablet has {}",
+                terms.join(symbol), place_notation(&expected), place_notation(written)
+            ))
+        }
+        Relation::Reciprocal => {
+            let [number, reciprocal] = numbers.as_slice() else {
+                return Some(format!("expected a reciprocal pair, found {} numbers", numbers.len()));
+            };
+            let product = number.checked_mul(reciprocal)?;
+            if is_power_of_sixty(&product) {
+                return None;
+            }
+            Some(format!(
+                "{} × {} = {}, which is not a power of 60",
+                place_notation(number), place_notation(reciprocal), place_notation(&product)
+            ))
+        }
+    }
+}
+
+fn to_exact(value: &Value) -> Option<SexagesimalNum> {
+    match value {
+        Value::Integer(i) => Some(SexagesimalNum::from_integer(*i)),
+        Value::BigInt(_) | Value::Radix(_) => {
+            let n = value.to_bigint()?;
+            let magnitude = n.magnitude().to_u64()?;
+            Some(SexagesimalNum::from_parts(n.is_negative(), magnitude, Vec::new()))
+        }
+        Value::Sexagesimal(sex) => Some(sex.clone()),
+        Value::Float(_) | Value::Floating(_) | Value::Quantity(_) => None,
+        Value::Boolean(_) | Value::Str(_) | Value::Function(_) | Value::Error(_) => None,
+        Value::List(_) | Value::Tuple(_) | Value::Map(_) | Value::Record(_) => None,
+    }
+}
+
+fn floating(number: &SexagesimalNum) -> FloatingNum {
+    FloatingNum::from_bigint(&number.to_scaled(number.places()))
+}
+
+/// Babylonian reciprocals are floating, so 2 and 30 are a pair because
+/// their product is 60: the digits must be a single 1 followed by zeros
+fn is_power_of_sixty(number: &SexagesimalNum) -> bool {
+    let scaled = number.to_scaled(number.places());
+    if scaled.is_negative() {
+        return false;
+    }
+    let places = cuneiform::integer_places(scaled.magnitude());
+    places[0] == 1 && places[1..].iter().all(|place| *place == 0)
+}
+
+/// Comma-separated places as in modern editions: 1,23;30
+fn place_notation(number: &SexagesimalNum) -> String {
+    let integer_places = cuneiform::integer_places(&BigUint::from(number.integer_part));
+    format::semicolon_notation(number.negative, &integer_places, &number.fractional_places)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TABLET: &str = "&P000001 = Example reciprocal table
+#atf: lang akk
+@obverse
+#abzu: reciprocal
+1. igi 2(diš) gal₂-bi 3(u)
+2. igi 3(diš) gal₂-bi 2(u)
+3. igi 9(diš) gal₂-bi 6(diš) 3(u)
+#abzu: sum
+4. 1(diš) & 2(u) & 2(u) 1(diš)
+5. 1(diš) & [2(u)]# & 3(u)
+#abzu: product
+6. 2(diš) & 3(u) & 1(diš)
+7. 2(diš) & 3(u) 5(diš) & 1(u)
+$ rest broken";
+
+    #[test]
+    fn test_numeric_lines() {
+        let lines = numeric_lines(TABLET);
+        
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0].line, 5);
+        assert_eq!(lines[0].label, "1.");
+        assert_eq!(lines[0].values, vec![Value::Integer(2), Value::Integer(30)]);
+        assert_eq!(lines[3].values, vec![Value::Integer(1), Value::Integer(20), Value::Integer(21)]);
+        assert_eq!(lines[4].values, vec![Value::Integer(1), Value::Integer(20), Value::Integer(30)]);
+    }
+
+    #[test]
+    fn test_check_reports_scribal_errors() {
+        let discrepancies = check(TABLET).unwrap();
+        
+        assert_eq!(discrepancies.len(), 3);
+        assert_eq!(discrepancies[0].line, 7);
+        assert_eq!(discrepancies[0].message, "9 × 6,30 = 58,30, which is not a power of 60");
+        assert_eq!(
+            discrepancies[1].to_string(),
+            "line 10 (5.): possible scribal error: 1 + 20 = 21, but the tablet has 30"
+        );
+        
+        // Products are floating: 2 × 30 is written 1, meaning 1,00
+        assert_eq!(discrepancies[2].line, 13);
+        assert_eq!(discrepancies[2].message, "2 × 35 = 1,10, but the tablet has 10");
+    }
+
+    #[test]
+    fn test_unknown_relation() {
+        assert!(matches!(check("#abzu: ratio"), Err(AtfError::UnknownRelation(1, _))));
+    }
+}
\ No newline at end of file
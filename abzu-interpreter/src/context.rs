@@ -0,0 +1,170 @@
+use crate::value::Rounding;
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContextError {
+    #[error("Unknown context setting: '{0}'")]
+    UnknownSetting(String),
+    #[error("Invalid value for {0}: '{1}'")]
+    InvalidValue(String, String),
+}
+
+/// What to do when an integer operation leaves the i64 range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverflowMode {
+    /// Raise `RuntimeError::Overflow`
+    Error,
+    /// Continue with an arbitrary-precision `Value::BigInt`
+    Promote,
+}
+
+/// Which kind of number wins when an operation mixes kinds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Promotion {
+    /// Integer < Sexagesimal < Float: floats are contagious, and inexact
+    /// integer quotients become floats
+    Float,
+    /// Integer < Float < Sexagesimal: sexagesimals are contagious, and
+    /// inexact integer quotients become sexagesimals
+    Sexagesimal,
+}
+
+/// Settings that decide the type and precision of arithmetic results
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericContext {
+    pub promotion: Promotion,
+    /// Fractional places kept in sexagesimal results
+    pub places: usize,
+    pub rounding: Rounding,
+    pub overflow: OverflowMode,
+    /// Raise an error whenever exact operands give an inexact result
+    pub strict: bool,
+}
+
+impl Default for NumericContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NumericContext {
+    pub const MAX_PLACES: usize = 20;
+
+    pub fn new() -> Self {
+        NumericContext {
+            promotion: Promotion::Float,
+            places: 4,
+            rounding: Rounding::HalfEven,
+            overflow: OverflowMode::Error,
+            strict: false,
+        }
+    }
+
+    /// Applies one `name = value` setting, as written in `with_context`
+    /// blocks and REPL commands
+    pub fn apply(&mut self, name: &str, value: &str) -> Result<(), ContextError> {
+        let invalid = || ContextError::InvalidValue(name.to_string(), value.to_string());
+        
+        match name {
+            "promotion" => {
+                self.promotion = match value {
+                    "float" => Promotion::Float,
+                    "sexagesimal" => Promotion::Sexagesimal,
+                    _ => return Err(invalid()),
+                };
+            }
+            "places" => {
+                self.places = value.parse::<usize>()
+                    .ok()
+                    .filter(|places| *places <= Self::MAX_PLACES)
+                    .ok_or_else(invalid)?;
+            }
+            "rounding" => {
+                self.rounding = match value {
+                    "half-even" | "half_even" => Rounding::HalfEven,
+                    "truncate" => Rounding::Truncate,
+                    "floor" => Rounding::Floor,
+                    _ => return Err(invalid()),
+                };
+            }
+            "overflow" => {
+                self.overflow = match value {
+                    "error" => OverflowMode::Error,
+                    "promote" => OverflowMode::Promote,
+                    _ => return Err(invalid()),
+                };
+            }
+            "strict" => {
+                self.strict = match value {
+                    "on" | "true" => true,
+                    "off" | "false" => false,
+                    _ => return Err(invalid()),
+                };
+            }
+            _ => return Err(ContextError::UnknownSetting(name.to_string())),
+        }
+        
+        Ok(())
+    }
+}
+
+impl fmt::Display for NumericContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let promotion = match self.promotion {
+            Promotion::Float => "float",
+            Promotion::Sexagesimal => "sexagesimal",
+        };
+        let overflow = match self.overflow {
+            OverflowMode::Error => "error",
+            OverflowMode::Promote => "promote",
+        };
+        
+        let strict = if self.strict { "on" } else { "off" };
+        
+        write!(
+            f,
+            "promotion={} places={} rounding={} overflow={} strict={}",
+            promotion, self.places, self.rounding, overflow, strict
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_settings() {
+        let mut context = NumericContext::new();
+        
+        context.apply("places", "6").unwrap();
+        context.apply("rounding", "truncate").unwrap();
+        context.apply("promotion", "sexagesimal").unwrap();
+        context.apply("overflow", "promote").unwrap();
+        context.apply("strict", "on").unwrap();
+        
+        assert_eq!(context.places, 6);
+        assert_eq!(context.rounding, Rounding::Truncate);
+        assert_eq!(context.promotion, Promotion::Sexagesimal);
+        assert_eq!(context.overflow, OverflowMode::Promote);
+        assert!(context.strict);
+        assert_eq!(
+            format!("{}", context),
+            "promotion=sexagesimal places=6 rounding=truncate overflow=promote strict=on"
+        );
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let mut context = NumericContext::new();
+        
+        assert!(matches!(context.apply("places", "-1"), Err(ContextError::InvalidValue(..))));
+        assert!(matches!(context.apply("places", "99"), Err(ContextError::InvalidValue(..))));
+        assert!(matches!(context.apply("rounding", "up"), Err(ContextError::InvalidValue(..))));
+        assert!(matches!(context.apply("radix", "60"), Err(ContextError::UnknownSetting(_))));
+        
+        // Failed settings leave the context untouched
+        assert_eq!(context, NumericContext::new());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,181 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{ToPrimitive, Zero};
+
+/// Default separator written between places
+pub const PLACE_SEPARATOR: &str = " ";
+
+/// Late Babylonian placeholder for an empty place (two oblique wedges)
+pub const ZERO_PLACE: char = '\u{12472}';
+
+const TENS: [char; 5] = ['\u{1230B}', '\u{12399}', '\u{1230D}', '\u{1240F}', '\u{12410}'];
+const UNITS: [char; 9] = [
+    '\u{12079}', '\u{1222B}', '\u{12408}', '\u{12409}', '\u{1240A}',
+    '\u{1240B}', '\u{1240C}', '\u{1240D}', '\u{1240E}',
+];
+
+/// Value of a cuneiform numeral sign, or None if `ch` is not one.
+///
+/// Place-value notation writes each base-60 place with units (diš,
+/// vertical wedges) and tens (u, Winkelhaken). The geš₂, gešu and šar₂
+/// signs of the absolute system count 60, 600 and 3600.
+pub fn numeral_value(ch: char) -> Option<u64> {
+    let value = match ch {
+        ZERO_PLACE => 0,
+        
+        // Units: diš, min, then THREE DISH .. NINE DISH
+        '\u{12079}' => 1,
+        '\u{1222B}' => 2,
+        '\u{12408}'..='\u{1240E}' => ch as u64 - 0x12408 + 3,
+        '\u{1243A}' | '\u{1243B}' => 3,
+        '\u{1243C}'..='\u{1243F}' => 4,
+        '\u{12440}' => 6,
+        '\u{12441}'..='\u{12443}' => 7,
+        '\u{12444}' | '\u{12445}' => 8,
+        '\u{12446}'..='\u{12449}' => 9,
+        
+        // Tens: u, u-u, u-u-u, then FOUR U .. NINE U
+        '\u{1230B}' => 10,
+        '\u{12399}' => 20,
+        '\u{1230D}' => 30,
+        '\u{1240F}'..='\u{12414}' => (ch as u64 - 0x1240F + 4) * 10,
+        '\u{12467}' => 40,
+        '\u{12468}' => 50,
+        '\u{12469}'..='\u{1246E}' => (ch as u64 - 0x12469 + 4) * 10,
+        
+        // Absolute system: ONE..NINE GESH2, ONE..FIVE GESHU, SHAR2
+        '\u{12415}'..='\u{1241D}' => (ch as u64 - 0x12415 + 1) * 60,
+        '\u{1241E}'..='\u{12422}' => (ch as u64 - 0x1241E + 1) * 600,
+        '\u{122B9}' => 3600,
+        '\u{12423}' | '\u{12424}' => (ch as u64 - 0x12423 + 2) * 3600,
+        '\u{12425}' => 3 * 3600,
+        '\u{12426}'..='\u{1242B}' => (ch as u64 - 0x12426 + 4) * 3600,
+        
+        _ => return None,
+    };
+    Some(value)
+}
+
+pub fn is_numeral(ch: char) -> bool {
+    numeral_value(ch).is_some()
+}
+
+/// Turns base-60 places read from cuneiform into a number literal that
+/// `parse_number` understands: [1, 30] -> "90", [1] ; [30] -> "1;30".
+///
+/// A lone integer place may exceed 59 when it uses absolute signs
+/// (𒐕𒌍 is 90); otherwise every place must be below 60.
+pub fn literal_from_places(integer_places: &[u64], fractional_places: &[u64]) -> Option<String> {
+    let positional = integer_places.len() > 1 || !fractional_places.is_empty();
+    if positional && integer_places.iter().chain(fractional_places).any(|place| *place >= 60) {
+        return None;
+    }
+    
+    let integer = integer_places.iter()
+        .fold(BigInt::from(0), |total, place| total * 60 + *place);
+    
+    if fractional_places.is_empty() {
+        return Some(integer.to_string());
+    }
+    let fraction: Vec<String> = fractional_places.iter().map(|place| place.to_string()).collect();
+    Some(format!("{};{}", integer, fraction.join(",")))
+}
+
+/// Base-60 places of an integer, most significant first; zero is [0]
+pub fn integer_places(integer: &BigUint) -> Vec<u8> {
+    let mut places = Vec::new();
+    let mut rest = integer.clone();
+    loop {
+        let (quotient, place) = rest.div_rem(&BigUint::from(60u8));
+        places.push(place.to_u8().unwrap_or(0));
+        rest = quotient;
+        if rest.is_zero() {
+            break;
+        }
+    }
+    places.reverse();
+    places
+}
+
+/// Wedge group for one base-60 place: tens sign followed by units sign
+pub fn place_glyphs(place: u8) -> String {
+    if place == 0 {
+        return ZERO_PLACE.to_string();
+    }
+    let (tens, units) = (place / 10, place % 10);
+    let mut glyphs = String::new();
+    if tens > 0 {
+        glyphs.push(TENS[tens as usize - 1]);
+    }
+    if units > 0 {
+        glyphs.push(UNITS[units as usize - 1]);
+    }
+    glyphs
+}
+
+/// Writes a number in cuneiform, one wedge group per place. The
+/// fractional places follow a ';', as the lexer expects.
+pub fn format_number(negative: bool, integer: &BigUint, fractional_places: &[u8], separator: &str) -> String {
+    let integer_places = integer_places(integer);
+    let groups = |places: &[u8]| places.iter()
+        .map(|place| place_glyphs(*place))
+        .collect::<Vec<_>>()
+        .join(separator);
+    
+    let sign = if negative { "-" } else { "" };
+    if fractional_places.is_empty() {
+        format!("{}{}", sign, groups(&integer_places))
+    } else {
+        format!("{}{};{}", sign, groups(&integer_places), groups(fractional_places))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeral_values() {
+        assert_eq!(numeral_value('𒁹'), Some(1));
+        assert_eq!(numeral_value('𒈫'), Some(2));
+        assert_eq!(numeral_value('𒐎'), Some(9));
+        assert_eq!(numeral_value('𒑆'), Some(9));
+        assert_eq!(numeral_value('𒌋'), Some(10));
+        assert_eq!(numeral_value('𒎙'), Some(20));
+        assert_eq!(numeral_value('𒐐'), Some(50));
+        assert_eq!(numeral_value('𒐕'), Some(60));
+        assert_eq!(numeral_value('𒐞'), Some(600));
+        assert_eq!(numeral_value('𒊹'), Some(3600));
+        assert_eq!(numeral_value('𒀭'), None);
+        assert_eq!(numeral_value('a'), None);
+    }
+
+    #[test]
+    fn test_literal_from_places() {
+        assert_eq!(literal_from_places(&[11], &[]), Some("11".to_string()));
+        assert_eq!(literal_from_places(&[1, 30], &[]), Some("90".to_string()));
+        assert_eq!(literal_from_places(&[1], &[30]), Some("1;30".to_string()));
+        assert_eq!(literal_from_places(&[1, 0], &[7, 30]), Some("60;7,30".to_string()));
+        assert_eq!(literal_from_places(&[90], &[]), Some("90".to_string()));
+        assert_eq!(literal_from_places(&[60, 1], &[]), None);
+        assert_eq!(literal_from_places(&[1], &[60]), None);
+    }
+
+    #[test]
+    fn test_place_glyphs_round_trip() {
+        for place in 0..60u8 {
+            let glyphs = place_glyphs(place);
+            let value: u64 = glyphs.chars().filter_map(numeral_value).sum();
+            assert_eq!(value, place as u64);
+        }
+    }
+
+    #[test]
+    fn test_format_number() {
+        let ninety = BigUint::from(90u8);
+        assert_eq!(format_number(false, &ninety, &[], PLACE_SEPARATOR), "𒁹 𒌍");
+        assert_eq!(format_number(true, &BigUint::from(1u8), &[24, 51, 10], ","), "-𒁹;𒎙𒐉,𒐐𒁹,𒌋");
+        assert_eq!(format_number(false, &BigUint::from(3600u16), &[], PLACE_SEPARATOR), "𒁹 𒑲 𒑲");
+        assert_eq!(format_number(false, &BigUint::zero(), &[], PLACE_SEPARATOR), "𒑲");
+    }
+}
\ No newline at end of file
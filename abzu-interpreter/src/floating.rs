@@ -0,0 +1,171 @@
+use crate::context::NumericContext;
+use crate::cuneiform;
+use crate::value::NumberError;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Signed, Zero};
+use std::fmt;
+
+/// A sexagesimal number as written on a tablet: the places are known
+/// but not the absolute magnitude, so 1,30 stands for 90, 1;30 and
+/// 0;01,30 alike. Values are equal when they agree up to a power of 60.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatingNum {
+    pub negative: bool,
+    /// Places with no leading or trailing zeros; empty for zero
+    pub places: Vec<u8>,
+}
+
+impl FloatingNum {
+    /// The places of `mantissa`, forgetting its magnitude
+    pub fn from_bigint(mantissa: &BigInt) -> Self {
+        let mut places = if mantissa.is_zero() {
+            Vec::new()
+        } else {
+            cuneiform::integer_places(mantissa.magnitude())
+        };
+        while places.last() == Some(&0) {
+            places.pop();
+        }
+        FloatingNum { negative: mantissa.is_negative(), places }
+    }
+    
+    /// The places read as an integer, with the last place as units
+    pub fn mantissa(&self) -> BigInt {
+        let magnitude = self.places.iter()
+            .fold(BigUint::zero(), |total, place| total * 60u8 + *place);
+        let mantissa = BigInt::from(magnitude);
+        if self.negative { -mantissa } else { mantissa }
+    }
+    
+    pub fn is_zero(&self) -> bool {
+        self.places.is_empty()
+    }
+    
+    pub fn negated(&self) -> Self {
+        FloatingNum { negative: !self.negative && !self.is_zero(), places: self.places.clone() }
+    }
+    
+    /// Multiplication needs no alignment, as in the scribal method
+    pub fn mul(&self, other: &Self) -> Self {
+        FloatingNum::from_bigint(&(self.mantissa() * other.mantissa()))
+    }
+    
+    /// igi: the reciprocal, which only exists as a finite sexagesimal
+    /// number for regular numbers (whose only prime factors are 2, 3 and
+    /// 5). Returns None for zero and for irregular numbers such as 7.
+    pub fn reciprocal(&self) -> Option<Self> {
+        let n = self.mantissa().abs();
+        if n.is_zero() {
+            return None;
+        }
+        
+        // 60^k / n is an integer once k covers every factor 2², 3 and 5
+        let mut rest = n.clone();
+        let mut k = 0u32;
+        for (prime, per_sixty) in [(2u32, 2u32), (3, 1), (5, 1)] {
+            let mut count: u32 = 0;
+            while (&rest % prime).is_zero() {
+                rest /= prime;
+                count += 1;
+            }
+            k = k.max(count.div_ceil(per_sixty));
+        }
+        if !rest.is_one() {
+            return None;
+        }
+        
+        let reciprocal = BigInt::from(60).pow(k) / n;
+        Some(FloatingNum::from_bigint(&if self.negative { -reciprocal } else { reciprocal }))
+    }
+    
+    /// Fixes the magnitude so that the last place counts 60^-places;
+    /// returns the scaled integer and the number of fractional places,
+    /// or None when places is beyond ±`NumericContext::MAX_PLACES`
+    pub fn anchor(&self, places: i64) -> Option<(BigInt, usize)> {
+        if places.unsigned_abs() > NumericContext::MAX_PLACES as u64 {
+            return None;
+        }
+        let mantissa = self.mantissa();
+        match usize::try_from(places) {
+            Ok(places) => Some((mantissa, places)),
+            Err(_) => Some((mantissa * BigInt::from(60).pow(places.unsigned_abs() as u32), 0)),
+        }
+    }
+}
+
+/// Parses the places of a floating literal such as `~1,30`
+pub fn parse_floating(s: &str) -> Result<FloatingNum, NumberError> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    
+    let mut mantissa = BigInt::zero();
+    for place in digits.split(',') {
+        let place = place.parse::<u8>()
+            .ok()
+            .filter(|place| *place < 60)
+            .ok_or_else(|| NumberError::InvalidFormat(s.to_string()))?;
+        mantissa = mantissa * 60 + place;
+    }
+    Ok(FloatingNum::from_bigint(&if negative { -mantissa } else { mantissa }))
+}
+
+impl fmt::Display for FloatingNum {
+    /// Places without a ';', marked with a leading ~: ~1,30
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "~0");
+        }
+        let places: Vec<String> = self.places.iter().map(|place| place.to_string()).collect();
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{}~{}", sign, places.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floating_equality() {
+        let ninety = FloatingNum::from_bigint(&BigInt::from(90));
+        let one_and_a_half = parse_floating("1,30").unwrap();
+        let scaled = FloatingNum::from_bigint(&BigInt::from(90 * 3600));
+        
+        assert_eq!(ninety, one_and_a_half);
+        assert_eq!(ninety, scaled);
+        assert_eq!(ninety.to_string(), "~1,30");
+        assert_eq!(parse_floating("0,0").unwrap().to_string(), "~0");
+    }
+
+    #[test]
+    fn test_scribal_multiplication() {
+        // 1,30 × 40 = 1 (floating 60)
+        let product = parse_floating("1,30").unwrap().mul(&parse_floating("40").unwrap());
+        assert_eq!(product.to_string(), "~1");
+    }
+
+    #[test]
+    fn test_reciprocals() {
+        let igi = |s: &str| parse_floating(s).unwrap().reciprocal().map(|r| r.to_string());
+        
+        assert_eq!(igi("2"), Some("~30".to_string()));
+        assert_eq!(igi("8"), Some("~7,30".to_string()));
+        assert_eq!(igi("1,4"), Some("~56,15".to_string()));
+        assert_eq!(igi("2,5"), Some("~28,48".to_string()));
+        assert_eq!(igi("7"), None);
+        assert_eq!(igi("0"), None);
+    }
+
+    #[test]
+    fn test_anchor() {
+        let number = parse_floating("1,30").unwrap();
+        
+        assert_eq!(number.anchor(0), Some((BigInt::from(90), 0)));
+        assert_eq!(number.anchor(1), Some((BigInt::from(90), 1)));
+        assert_eq!(number.anchor(-1), Some((BigInt::from(5400), 0)));
+        assert_eq!(number.anchor(21), None);
+        assert_eq!(number.anchor(i64::MIN), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,266 @@
+use crate::context::NumericContext;
+use crate::cuneiform;
+use crate::value::{format_transliteration, SexagesimalNum, Value};
+use num_bigint::BigUint;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FormatError {
+    #[error("Invalid format spec '{0}': {1}")]
+    InvalidSpec(String, String),
+    #[error("Cannot format {0} as a number")]
+    NotANumber(String),
+    #[error("{0} is too large to write with {1} sexagesimal places")]
+    Overflow(String, usize),
+}
+
+/// How places are written in base 60
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaceNotation {
+    /// 1,30;05: commas between places, ';' before the fraction
+    Semicolon,
+    /// 1,30,05: commas only, with the fractional boundary unmarked
+    Comma,
+    Cuneiform,
+    /// ATF: 1(diš) 3(u) ; 5(diš)
+    Transliteration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// The part of an interpolated field after the ':', as in {x:60.3}.
+/// Written `[base][.places][notation][<width|>width]`, where the base is
+/// 10 or 60 and the notation is ';', ',', 'c' (cuneiform) or 't'
+/// (transliteration). An empty spec writes the value as it displays.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FormatSpec {
+    pub base: Option<u32>,
+    /// Fractional places, or decimal digits in base 10
+    pub places: Option<usize>,
+    pub notation: Option<PlaceNotation>,
+    /// Minimum width in characters
+    pub width: Option<(Align, usize)>,
+}
+
+fn split_digits(s: &str) -> (&str, &str) {
+    s.split_at(s.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(s.len()))
+}
+
+/// Parses a format spec such as `60.3`, `60c`, `10.2>8` or `<12`
+pub fn parse_spec(spec: &str) -> Result<FormatSpec, FormatError> {
+    let invalid = |reason: &str| FormatError::InvalidSpec(spec.to_string(), reason.to_string());
+    let mut parsed = FormatSpec::default();
+    
+    let (base, mut rest) = split_digits(spec);
+    if !base.is_empty() {
+        parsed.base = match base {
+            "10" => Some(10),
+            "60" => Some(60),
+            _ => return Err(invalid("the base must be 10 or 60")),
+        };
+    }
+    
+    if let Some(after) = rest.strip_prefix('.') {
+        let (places, after) = split_digits(after);
+        parsed.places = match places.parse::<usize>() {
+            Ok(places) if places <= NumericContext::MAX_PLACES => Some(places),
+            _ => return Err(invalid(&format!("expected 0 to {} places after '.'", NumericContext::MAX_PLACES))),
+        };
+        rest = after;
+    }
+    
+    parsed.notation = match rest.chars().next() {
+        Some(';') => Some(PlaceNotation::Semicolon),
+        Some(',') => Some(PlaceNotation::Comma),
+        Some('c') => Some(PlaceNotation::Cuneiform),
+        Some('t') => Some(PlaceNotation::Transliteration),
+        _ => None,
+    };
+    if parsed.notation.is_some() {
+        rest = &rest[1..];
+    }
+    if parsed.notation.is_some() && parsed.base == Some(10) {
+        return Err(invalid("notations only apply to base 60"));
+    }
+    
+    let align = match rest.chars().next() {
+        Some('<') => Align::Left,
+        Some('>') => Align::Right,
+        None => return Ok(parsed),
+        Some(_) => return Err(invalid(&format!("unexpected '{}'", rest))),
+    };
+    match rest[1..].parse::<usize>() {
+        Ok(width) => parsed.width = Some((align, width)),
+        Err(_) => return Err(invalid("expected a width after '<' or '>'")),
+    }
+    Ok(parsed)
+}
+
+/// Writes a value as a format spec asks. Inexact numbers keep their ≈
+/// mark; sexagesimal places are rounded with the context rounding, and
+/// floats without a place count get the context places.
+pub fn format_value(value: &Value, spec: &FormatSpec, context: &NumericContext) -> Result<String, FormatError> {
+    let text = if spec.base.is_none() && spec.places.is_none() && spec.notation.is_none() {
+        value.to_string()
+    } else {
+        format_number(value, spec, context)?
+    };
+    
+    let Some((align, width)) = spec.width else {
+        return Ok(text);
+    };
+    let fill = " ".repeat(width.saturating_sub(text.chars().count()));
+    Ok(match align {
+        Align::Left => text + &fill,
+        Align::Right => fill + &text,
+    })
+}
+
+fn format_number(value: &Value, spec: &FormatSpec, context: &NumericContext) -> Result<String, FormatError> {
+    if let Value::Quantity(q) = value {
+        return Ok(format!("{} {}", format_number(&q.amount, spec, context)?, q.unit.name));
+    }
+    let not_a_number = || FormatError::NotANumber(value.to_string());
+    if !value.is_number() {
+        return Err(not_a_number());
+    }
+    
+    // Without a base, numbers stay in the base they were written in
+    let sexagesimal = matches!(value, Value::Sexagesimal(_) | Value::Floating(_));
+    if spec.base == Some(10) || (spec.base.is_none() && spec.notation.is_none() && !sexagesimal) {
+        return decimal(value, spec.places).ok_or_else(not_a_number);
+    }
+    
+    let (exact, negative, integer, fractional) = sexagesimal_parts(value, spec.places, context)?;
+    let integer_places = cuneiform::integer_places(&integer);
+    let sign = if negative { "-" } else { "" };
+    let text = match spec.notation.unwrap_or(PlaceNotation::Semicolon) {
+        PlaceNotation::Semicolon => semicolon_notation(negative, &integer_places, &fractional),
+        PlaceNotation::Comma => {
+            let places: Vec<u8> = integer_places.iter().chain(&fractional).copied().collect();
+            format!("{}{}", sign, place_digits(&places))
+        }
+        PlaceNotation::Cuneiform => {
+            cuneiform::format_number(negative, &integer, &fractional, cuneiform::PLACE_SEPARATOR)
+        }
+        PlaceNotation::Transliteration => format_transliteration(negative, &integer_places, &fractional),
+    };
+    Ok(if exact { text } else { format!("≈{}", text) })
+}
+
+/// Modern edition notation, with ';' before the fraction: 1,05;30,05
+pub(crate) fn semicolon_notation(negative: bool, integer_places: &[u8], fractional: &[u8]) -> String {
+    let sign = if negative { "-" } else { "" };
+    if fractional.is_empty() {
+        return format!("{}{}", sign, place_digits(integer_places));
+    }
+    let fraction: Vec<String> = fractional.iter().map(|place| format!("{:02}", place)).collect();
+    format!("{}{};{}", sign, place_digits(integer_places), fraction.join(","))
+}
+
+/// Places joined by commas, two digits each after the first: 1,05,30
+fn place_digits(places: &[u8]) -> String {
+    places.iter()
+        .enumerate()
+        .map(|(index, place)| if index == 0 { place.to_string() } else { format!("{:02}", place) })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Exactness, sign, integer and fractional places of a number, rounded
+/// to `places` fractional places
+fn sexagesimal_parts(
+    value: &Value,
+    places: Option<usize>,
+    context: &NumericContext,
+) -> Result<(bool, bool, BigUint, Vec<u8>), FormatError> {
+    let overflow = |places| FormatError::Overflow(value.to_string(), places);
+    let sex = match value {
+        Value::Sexagesimal(sex) => sex.round_to(places.unwrap_or(usize::MAX), context.rounding)
+            .ok_or_else(|| overflow(places.unwrap_or(sex.places())))?,
+        Value::Float(n) if n.is_finite() => {
+            let places = places.unwrap_or(context.places);
+            SexagesimalNum::from_f64_rounded(*n, places, context.rounding).ok_or_else(|| overflow(places))?
+        }
+        Value::Radix(r) if r.to_bigint().is_none() => {
+            let places = places.unwrap_or(context.places);
+            r.to_sexagesimal(places, context.rounding).ok_or_else(|| overflow(places))?
+        }
+        _ => {
+            let (negative, integer, fractional) = value.to_places(context.places)
+                .ok_or_else(|| FormatError::NotANumber(value.to_string()))?;
+            return Ok((value.is_exact(), negative, integer, fractional));
+        }
+    };
+    Ok((sex.exact, sex.negative, BigUint::from(sex.integer_part), sex.fractional_places))
+}
+
+/// Base-10 digits; integers stay exact, other numbers go through f64
+fn decimal(value: &Value, places: Option<usize>) -> Option<String> {
+    if matches!(value, Value::Floating(_)) {
+        return None;
+    }
+    let text = match (value.to_bigint(), places) {
+        (Some(n), None | Some(0)) => n.to_string(),
+        (Some(n), Some(places)) => format!("{}.{}", n, "0".repeat(places)),
+        (None, Some(places)) => format!("{:.*}", places, value.to_f64()),
+        (None, None) => value.to_f64().to_string(),
+    };
+    Some(if value.is_exact() { text } else { format!("≈{}", text) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::parse_number;
+    
+    fn format(literal: &str, spec: &str) -> String {
+        let spec = parse_spec(spec).unwrap();
+        format_value(&parse_number(literal).unwrap(), &spec, &NumericContext::new()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = parse_spec("60.3c>12").unwrap();
+        assert_eq!(spec.base, Some(60));
+        assert_eq!(spec.places, Some(3));
+        assert_eq!(spec.notation, Some(PlaceNotation::Cuneiform));
+        assert_eq!(spec.width, Some((Align::Right, 12)));
+        assert_eq!(parse_spec("").unwrap(), FormatSpec::default());
+        
+        assert!(parse_spec("16").is_err());
+        assert!(parse_spec("10c").is_err());
+        assert!(parse_spec("60.").is_err());
+        assert!(parse_spec("60x").is_err());
+    }
+
+    #[test]
+    fn test_sexagesimal_notations() {
+        assert_eq!(format("1;24,51,10", "60.2"), "≈1;24,51");
+        assert_eq!(format("1;24,51,10", "60.1"), "≈1;25");
+        assert_eq!(format("1;24,51,10", "60;"), "1;24,51,10");
+        assert_eq!(format("90", "60"), "1,30");
+        assert_eq!(format("90;05", "60,"), "1,30,05");
+        assert_eq!(format("1;30", "60c"), "𒁹;𒌍");
+        assert_eq!(format("1;30", "t"), "1(diš) ; 3(u)");
+        assert_eq!(format("1.5", "60"), "≈1;30");
+        
+        // Many places are scaled exactly; integer parts beyond u64 overflow
+        assert_eq!(format("1000.5", "60.20"), "≈16,40;30");
+        let spec = parse_spec("60.20").unwrap();
+        let huge = format_value(&Value::Float(1e30), &spec, &NumericContext::new());
+        assert!(matches!(huge, Err(FormatError::Overflow(..))));
+    }
+
+    #[test]
+    fn test_decimal_and_width() {
+        assert_eq!(format("1;30", "10.3"), "1.500");
+        assert_eq!(format("7", "10.2"), "7.00");
+        assert_eq!(format("7", ">4"), "   7");
+        assert_eq!(format("1;30", "60<6"), "1;30  ");
+    }
+}
\ No newline at end of file
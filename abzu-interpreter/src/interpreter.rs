@@ -1,6 +1,20 @@
-use crate::ast::{Program, Statement, Expression, Operator};
-use crate::value::{Value, SexagesimalNum, parse_number};
+use crate::ast::{
+    Program, Statement, Expression, FormatPart, MatchArm, Operator, Pattern, TryCatch, TypeDecl, Variant, WithContext,
+};
+use crate::context::{NumericContext, OverflowMode, Promotion};
+use crate::floating::FloatingNum;
+use crate::format::{self, FormatError};
+use crate::metrology::{self, Dimension, Quantity, Unit};
+use crate::radix::RadixNum;
+use crate::svg;
+use crate::typeset::{self, Places};
+use crate::value::{ErrorValue, Function, Record, Value, SexagesimalNum, parse_number, power_fits};
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
 use thiserror::Error;
+use std::cell::Cell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 #[derive(Error, Debug)]
@@ -13,17 +27,97 @@ pub enum RuntimeError {
     DivisionByZero,
     #[error("Invalid operator for types: {0}")]
     InvalidOperator(String),
+    #[error("Integer overflow: {0}")]
+    Overflow(String),
+    #[error("Context error: {0}")]
+    ContextError(String),
+    #[error("Inexact result in strict mode: {0}")]
+    InexactResult(String),
+    #[error("Undefined function: '{0}'")]
+    UndefinedFunction(String),
+    #[error("Wrong number of arguments: {0}")]
+    ArityMismatch(String),
+    #[error("{0} has no finite sexagesimal reciprocal")]
+    IrregularNumber(String),
+    #[error("Unit mismatch: {0}")]
+    UnitMismatch(String),
+    #[error("Unknown unit: '{0}'")]
+    UnknownUnit(String),
+    #[error("Index out of range: {0}")]
+    IndexOutOfRange(String),
+    #[error("No field '{0}' in {1}")]
+    MissingField(String, String),
+    #[error("Shape mismatch: {0}")]
+    ShapeMismatch(String),
+    #[error("Non-exhaustive match: {0}")]
+    NonExhaustiveMatch(String),
+    #[error("No match arm fits {0}")]
+    NoMatch(String),
+    #[error("Uncaught {0}")]
+    Thrown(ErrorValue),
 }
 
-#[derive(Debug, Clone)]
+impl RuntimeError {
+    /// The error as a value for a catch block: thrown errors come back
+    /// as thrown, others get their variant name as kind
+    pub fn to_value(&self) -> ErrorValue {
+        let kind = match self {
+            RuntimeError::Thrown(error) => return error.clone(),
+            RuntimeError::UndefinedVariable(_) => "UndefinedVariable",
+            RuntimeError::TypeError(_) => "TypeError",
+            RuntimeError::DivisionByZero => "DivisionByZero",
+            RuntimeError::InvalidOperator(_) => "InvalidOperator",
+            RuntimeError::Overflow(_) => "Overflow",
+            RuntimeError::ContextError(_) => "ContextError",
+            RuntimeError::InexactResult(_) => "InexactResult",
+            RuntimeError::UndefinedFunction(_) => "UndefinedFunction",
+            RuntimeError::ArityMismatch(_) => "ArityMismatch",
+            RuntimeError::IrregularNumber(_) => "IrregularNumber",
+            RuntimeError::UnitMismatch(_) => "UnitMismatch",
+            RuntimeError::UnknownUnit(_) => "UnknownUnit",
+            RuntimeError::IndexOutOfRange(_) => "IndexOutOfRange",
+            RuntimeError::MissingField(_, _) => "MissingField",
+            RuntimeError::ShapeMismatch(_) => "ShapeMismatch",
+            RuntimeError::NonExhaustiveMatch(_) => "NonExhaustiveMatch",
+            RuntimeError::NoMatch(_) => "NoMatch",
+        };
+        ErrorValue { kind: kind.to_string(), message: self.to_string() }
+    }
+}
+
+/// Names call_builtin answers to; an identifier naming one evaluates to
+/// the builtin as a function value, so that map(xs, igi) works
+const BUILTINS: &[&str] = &[
+    "exact?", "inexact?", "floating", "igi", "anchor", "radix", "latex", "mathml", "svg",
+    "len", "slice", "upper", "split", "join",
+    "push", "map", "filter", "reduce", "sum", "product", "sort", "zip",
+    "keys", "values", "items", "divmod", "table", "error", "error?",
+];
+
+/// Longest list a range may build
+const MAX_RANGE_ITEMS: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Environment {
     variables: HashMap<String, Value>,
+    /// Variants of each declared type; a struct has one, named after it
+    types: HashMap<String, Vec<Variant>>,
+    /// The type each variant name is declared by
+    variant_types: HashMap<String, String>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Environment {
     pub fn new() -> Self {
         Self {
             variables: HashMap::new(),
+            types: HashMap::new(),
+            variant_types: HashMap::new(),
         }
     }
     
@@ -34,13 +128,79 @@ impl Environment {
     pub fn get(&self, name: &str) -> Option<Value> {
         self.variables.get(name).cloned()
     }
+    
+    /// Runs `body` with `bindings` set, then gives those names back the
+    /// values they had before, so pattern and catch variables stay local
+    /// while other assignments made in `body` are kept
+    fn with_bindings<T>(
+        &mut self,
+        bindings: Vec<(String, Value)>,
+        body: impl FnOnce(&mut Environment) -> T,
+    ) -> T {
+        let shadowed: Vec<(String, Option<Value>)> = bindings.iter()
+            .map(|(name, _)| (name.clone(), self.get(name)))
+            .collect();
+        for (name, value) in bindings {
+            self.set(name, value);
+        }
+        let result = body(self);
+        for (name, previous) in shadowed.into_iter().rev() {
+            match previous {
+                Some(value) => self.set(name, value),
+                None => {
+                    self.variables.remove(&name);
+                }
+            }
+        }
+        result
+    }
+    
+    /// The declared type a variant belongs to, and the variant itself
+    fn find_variant(&self, name: &str) -> Option<(&str, &Variant)> {
+        let type_name = self.variant_types.get(name)?;
+        let variant = self.types.get(type_name)?.iter().find(|variant| variant.name == name)?;
+        Some((type_name.as_str(), variant))
+    }
+    
+    /// A variant without fields, which a bare name in a pattern refers to
+    fn unit_variant(&self, name: &str) -> Option<(&str, &Variant)> {
+        self.find_variant(name).filter(|(_, variant)| variant.fields.is_empty())
+    }
+}
+
+/// Both operands of a binary operation, converted to the common kind
+/// chosen by the context's promotion rules
+enum Operands {
+    Integer(i64, i64),
+    BigInt(BigInt, BigInt),
+    Float(f64, f64),
+    Sexagesimal(SexagesimalNum, SexagesimalNum),
+}
+
+pub struct Interpreter {
+    // A Cell so that `with_context` blocks can swap it during evaluation
+    context: Cell<NumericContext>,
 }
 
-pub struct Interpreter;
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Interpreter {
     pub fn new() -> Self {
-        Interpreter
+        Interpreter {
+            context: Cell::new(NumericContext::new()),
+        }
+    }
+    
+    pub fn context(&self) -> NumericContext {
+        self.context.get()
+    }
+    
+    pub fn set_context(&mut self, context: NumericContext) {
+        self.context.set(context);
     }
     
     pub fn eval_program(
@@ -51,25 +211,332 @@ impl Interpreter {
         let mut result = None;
         
         for statement in &program.statements {
-            result = Some(self.eval_statement(statement, environment)?);
+            result = self.eval_statement(statement, environment)?;
         }
         
         Ok(result)
     }
     
+    /// The value of a statement; None for declarations, which have none
     fn eval_statement(
         &self, 
         statement: &Statement, 
         environment: &mut Environment
-    ) -> Result<Value, RuntimeError> {
-        match statement {
-            Statement::Expression(expr) => self.eval_expression(expr, environment),
+    ) -> Result<Option<Value>, RuntimeError> {
+        let value = match statement {
+            Statement::Expression(expr) => self.eval_expression(expr, environment)?,
             Statement::Assignment(assign) => {
                 let value = self.eval_expression(&assign.value, environment)?;
                 environment.set(assign.variable.clone(), value.clone());
-                Ok(value)
+                value
+            }
+            // (q, r) = divmod(a, b): nothing is bound unless the whole
+            // pattern fits
+            Statement::Destructure(destructure) => {
+                let value = self.eval_expression(&destructure.value, environment)?;
+                let mut bindings = Vec::new();
+                self.destructure(&destructure.pattern, &value, &mut bindings, environment)?;
+                for (name, bound) in bindings {
+                    environment.set(name, bound);
+                }
+                value
+            }
+            Statement::TypeDecl(decl) => {
+                self.declare_type(decl, environment)?;
+                return Ok(None);
+            }
+            Statement::WithContext(block) => self.eval_with_context(block, environment)?,
+        };
+        Ok(Some(value))
+    }
+    
+    /// `type Point = Point(x, y)` or `type Shape = Square(side) | Empty`.
+    /// Variants with fields are bound as constructors and variants
+    /// without are bound as values.
+    fn declare_type(&self, decl: &TypeDecl, environment: &mut Environment) -> Result<(), RuntimeError> {
+        for (index, variant) in decl.variants.iter().enumerate() {
+            if decl.variants[..index].iter().any(|other| other.name == variant.name) {
+                return Err(RuntimeError::TypeError(
+                    format!("Variant {} is declared twice in {}", variant.name, decl.name)
+                ));
+            }
+            if let Some(field) = variant.fields.iter().enumerate()
+                .find_map(|(i, field)| variant.fields[..i].contains(field).then_some(field)) {
+                return Err(RuntimeError::TypeError(
+                    format!("Field {} is declared twice in {}", field, variant.name)
+                ));
+            }
+            if let Some((other, _)) = environment.find_variant(&variant.name)
+                && other != decl.name {
+                return Err(RuntimeError::TypeError(
+                    format!("Variant {} is already declared by {}", variant.name, other)
+                ));
+            }
+        }
+        
+        // A redeclared type gives up its old variants
+        if let Some(old) = environment.types.insert(decl.name.clone(), decl.variants.clone()) {
+            for variant in old {
+                environment.variant_types.remove(&variant.name);
+            }
+        }
+        for variant in &decl.variants {
+            environment.variant_types.insert(variant.name.clone(), decl.name.clone());
+            let value = if variant.fields.is_empty() {
+                Value::Record(Record { type_name: decl.name.clone(), variant: variant.name.clone(), fields: Vec::new() })
+            } else {
+                Value::Function(Function::Constructor {
+                    type_name: decl.name.clone(),
+                    variant: variant.name.clone(),
+                    fields: variant.fields.clone(),
+                })
+            };
+            environment.set(variant.name.clone(), value);
+        }
+        Ok(())
+    }
+    
+    /// Runs the first arm whose pattern fits and whose guard holds. The
+    /// names a pattern binds are local to its arm; other assignments made
+    /// in the guard or body are kept.
+    fn eval_match(&self, value: &Value, arms: &[MatchArm], environment: &mut Environment) -> Result<Value, RuntimeError> {
+        self.check_exhaustive(arms, environment)?;
+        for arm in arms {
+            let mut bindings = Vec::new();
+            if !self.match_pattern(&arm.pattern, value, &mut bindings, environment)? {
+                continue;
+            }
+            let result = environment.with_bindings(bindings, |scope| {
+                if let Some(guard) = &arm.guard {
+                    match self.eval_expression(guard, scope)? {
+                        Value::Boolean(true) => {}
+                        Value::Boolean(false) => return Ok(None),
+                        other => return Err(RuntimeError::TypeError(
+                            format!("A match guard must be true or false, got {}", other)
+                        )),
+                    }
+                }
+                self.eval_expression(&arm.body, scope).map(Some)
+            })?;
+            if let Some(result) = result {
+                return Ok(result);
+            }
+        }
+        Err(RuntimeError::NoMatch(value.to_string()))
+    }
+    
+    /// Rejects matches that cannot cover every value. A match on variants
+    /// must name each variant of the type, and literal patterns other than
+    /// true and false never cover everything; both are fine with a
+    /// catch-all arm. Guarded arms cover nothing, since the guard may fail.
+    /// Patterns on tuples and lists are left to fail at run time.
+    fn check_exhaustive(&self, arms: &[MatchArm], environment: &Environment) -> Result<(), RuntimeError> {
+        let unguarded: Vec<&Pattern> = arms.iter()
+            .filter(|arm| arm.guard.is_none())
+            .map(|arm| &arm.pattern)
+            .collect();
+        let catch_all = unguarded.iter().any(|pattern| match pattern {
+            Pattern::Wildcard => true,
+            Pattern::Name(name) => environment.unit_variant(name).is_none(),
+            _ => false,
+        });
+        if catch_all {
+            return Ok(());
+        }
+        
+        let variant_name = |pattern: &Pattern| match pattern {
+            Pattern::Variant(name, _) => Some(name.clone()),
+            Pattern::Name(name) => environment.unit_variant(name).map(|_| name.clone()),
+            _ => None,
+        };
+        let first_variant = arms.iter().find_map(|arm| variant_name(&arm.pattern));
+        if let Some((type_name, _)) = first_variant.and_then(|name| environment.find_variant(&name)) {
+            let covered: Vec<String> = unguarded.iter().filter_map(|pattern| variant_name(pattern)).collect();
+            let missing: Vec<&str> = environment.types[type_name].iter()
+                .map(|variant| variant.name.as_str())
+                .filter(|name| !covered.iter().any(|covered| covered == name))
+                .collect();
+            if !missing.is_empty() {
+                return Err(RuntimeError::NonExhaustiveMatch(
+                    format!("{} is not covered; add its arms or a _ arm", missing.join(", "))
+                ));
+            }
+            return Ok(());
+        }
+        
+        let literal = |pattern: &&Pattern, name: &str| {
+            matches!(pattern, Pattern::Literal(Expression::Identifier(word)) if word == name)
+        };
+        let booleans = unguarded.iter().any(|pattern| literal(pattern, "true"))
+            && unguarded.iter().any(|pattern| literal(pattern, "false"));
+        if !booleans && arms.iter().all(|arm| matches!(arm.pattern, Pattern::Literal(_))) {
+            return Err(RuntimeError::NonExhaustiveMatch(
+                "literal patterns cannot cover every value; add a _ arm".to_string()
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Tries a pattern against a value, collecting the names it binds.
+    /// A bare name that is a variant without fields matches that variant
+    /// instead of binding.
+    fn match_pattern(
+        &self,
+        pattern: &Pattern,
+        value: &Value,
+        bindings: &mut Vec<(String, Value)>,
+        environment: &mut Environment,
+    ) -> Result<bool, RuntimeError> {
+        match pattern {
+            Pattern::Name(name) if environment.unit_variant(name).is_some() => {
+                Ok(matches!(value, Value::Record(record) if &record.variant == name))
+            }
+            Pattern::Name(name) => {
+                bindings.push((name.clone(), value.clone()));
+                Ok(true)
+            }
+            Pattern::Wildcard => Ok(true),
+            Pattern::Literal(literal) => {
+                let literal = self.eval_expression(literal, environment)?;
+                Ok(self.compare_values(value, &literal)? == Some(Ordering::Equal))
+            }
+            Pattern::Tuple(patterns) | Pattern::List(patterns) => {
+                let items = match (pattern, value) {
+                    (Pattern::Tuple(_), Value::Tuple(items)) | (Pattern::List(_), Value::List(items)) => items,
+                    _ => return Ok(false),
+                };
+                if items.len() != patterns.len() {
+                    return Ok(false);
+                }
+                for (pattern, item) in patterns.iter().zip(items) {
+                    if !self.match_pattern(pattern, item, bindings, environment)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Pattern::Variant(name, patterns) => {
+                let Some((type_name, variant)) = environment.find_variant(name) else {
+                    return Err(RuntimeError::UndefinedFunction(name.clone()));
+                };
+                if variant.fields.len() != patterns.len() {
+                    return Err(RuntimeError::ShapeMismatch(
+                        format!("{} has {} field(s), the pattern gives {}", name, variant.fields.len(), patterns.len())
+                    ));
+                }
+                let Value::Record(record) = value else {
+                    return Ok(false);
+                };
+                if record.type_name != type_name || &record.variant != name {
+                    return Ok(false);
+                }
+                for (pattern, (_, field)) in patterns.iter().zip(&record.fields) {
+                    if !self.match_pattern(pattern, field, bindings, environment)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+    
+    /// Matches a value against an assignment pattern, collecting the
+    /// names it binds. Tuple patterns take tuples and list patterns take
+    /// lists, each of exactly the pattern's length.
+    fn destructure(
+        &self,
+        pattern: &Pattern,
+        value: &Value,
+        bindings: &mut Vec<(String, Value)>,
+        environment: &mut Environment,
+    ) -> Result<(), RuntimeError> {
+        let expected = |shape: &str, len: usize| RuntimeError::ShapeMismatch(
+            format!("expected a {} of {} to unpack, got {}", shape, len, value)
+        );
+        let (patterns, items, shape) = match (pattern, value) {
+            (Pattern::Name(name), _) => {
+                bindings.push((name.clone(), value.clone()));
+                return Ok(());
             }
+            (Pattern::Wildcard, _) => return Ok(()),
+            (Pattern::Tuple(patterns), Value::Tuple(items)) => (patterns, items, "tuple"),
+            (Pattern::List(patterns), Value::List(items)) => (patterns, items, "list"),
+            (Pattern::Tuple(patterns), _) => return Err(expected("tuple", patterns.len())),
+            (Pattern::List(patterns), _) => return Err(expected("list", patterns.len())),
+            // Square(side) = shape
+            (Pattern::Literal(_) | Pattern::Variant(..), _) => {
+                return match self.match_pattern(pattern, value, bindings, environment)? {
+                    true => Ok(()),
+                    false => Err(RuntimeError::ShapeMismatch(format!("{} does not fit the pattern", value))),
+                };
+            }
+        };
+        if patterns.len() != items.len() {
+            return Err(RuntimeError::ShapeMismatch(
+                format!("cannot unpack {} ({} items) into a {} of {}", value, items.len(), shape, patterns.len())
+            ));
+        }
+        for (pattern, item) in patterns.iter().zip(items) {
+            self.destructure(pattern, item, bindings, environment)?;
+        }
+        Ok(())
+    }
+    
+    /// Runs a block under modified settings, e.g.
+    /// `with_context places = 6, rounding = truncate { ... }`,
+    /// restoring the outer context afterwards even if the block fails
+    fn eval_with_context(
+        &self, 
+        block: &WithContext, 
+        environment: &mut Environment
+    ) -> Result<Value, RuntimeError> {
+        let mut context = self.context.get();
+        for (name, setting) in &block.settings {
+            // Settings are bare words or numbers, not evaluated expressions
+            let value = match setting {
+                Expression::Identifier(word) | Expression::Number(word) => word,
+                _ => return Err(RuntimeError::ContextError(
+                    format!("{} must be set to a plain word or number", name)
+                )),
+            };
+            context.apply(name, value)
+                .map_err(|e| RuntimeError::ContextError(e.to_string()))?;
+        }
+        
+        let outer = self.context.replace(context);
+        let result = block.body.iter()
+            .map(|statement| self.eval_statement(statement, environment))
+            .collect::<Result<Vec<_>, _>>();
+        self.context.set(outer);
+        
+        result?.pop().flatten().ok_or_else(|| RuntimeError::ContextError(
+            "with_context block does not end in a value".to_string()
+        ))
+    }
+    
+    /// `try { ... } catch e { ... }`: if the body fails, the error is
+    /// bound to e as a value and the handler runs instead. Every runtime
+    /// error is caught, not only `throw`: an irregular igi, a division by
+    /// zero or an undefined name alike, with its kind in e.kind. e is
+    /// local to the handler; assignments made before the failure, and in
+    /// the handler, are kept.
+    fn eval_try(&self, block: &TryCatch, environment: &mut Environment) -> Result<Value, RuntimeError> {
+        match self.eval_block(&block.body, environment, "try") {
+            Ok(value) => Ok(value),
+            Err(error) => {
+                let bindings = vec![(block.error.clone(), Value::Error(error.to_value()))];
+                environment.with_bindings(bindings, |scope| self.eval_block(&block.handler, scope, "catch"))
+            }
+        }
+    }
+    
+    /// Runs statements in order; the last one gives the block's value
+    fn eval_block(&self, statements: &[Statement], environment: &mut Environment, name: &str) -> Result<Value, RuntimeError> {
+        let mut result = None;
+        for statement in statements {
+            result = self.eval_statement(statement, environment)?;
         }
+        result.ok_or_else(|| RuntimeError::TypeError(format!("{} block does not end in a value", name)))
     }
     
     fn eval_expression(
@@ -79,13 +546,120 @@ impl Interpreter {
     ) -> Result<Value, RuntimeError> {
         match expr {
             Expression::Number(n_str) => {
-                parse_number(n_str)
-                    .map_err(|e| RuntimeError::TypeError(e.to_string()))
+                let value = parse_number(n_str)
+                    .map_err(|e| RuntimeError::TypeError(e.to_string()))?;
+                // Literals beyond i64 follow the overflow mode like results do
+                if let Value::BigInt(_) = value
+                    && self.context.get().overflow == OverflowMode::Error
+                {
+                    return Err(RuntimeError::Overflow(format!(
+                        "{} does not fit in a 64-bit integer; use :overflow promote", n_str
+                    )));
+                }
+                Ok(value)
+            }
+            Expression::Str(text) => Ok(Value::Str(text.clone())),
+            // f"side = {x:60.3}"
+            Expression::Format(parts) => {
+                let mut text = String::new();
+                for part in parts {
+                    match part {
+                        FormatPart::Text(s) => text.push_str(s),
+                        FormatPart::Field(expr, spec) => {
+                            let value = self.eval_expression(expr, environment)?;
+                            let formatted = format::format_value(&value, spec, &self.context.get())
+                                .map_err(|e| match e {
+                                    FormatError::Overflow(..) => RuntimeError::Overflow(e.to_string()),
+                                    _ => RuntimeError::TypeError(e.to_string()),
+                                })?;
+                            text.push_str(&formatted);
+                        }
+                    }
+                }
+                Ok(Value::Str(text))
             }
             Expression::Identifier(id) => {
                 environment.get(id)
+                    .or_else(|| BUILTINS.contains(&id.as_str()).then(|| Value::Function(Function::Builtin(id.clone()))))
+                    .or(match id.as_str() {
+                        "true" => Some(Value::Boolean(true)),
+                        "false" => Some(Value::Boolean(false)),
+                        _ => None,
+                    })
                     .ok_or_else(|| RuntimeError::UndefinedVariable(id.clone()))
             }
+            // [1,30, 2]: the adjacency rule in the lexer keeps 1,30 whole
+            Expression::List(items) => {
+                items.iter()
+                    .map(|item| self.eval_expression(item, environment))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Value::List)
+            }
+            Expression::Tuple(items) => {
+                items.iter()
+                    .map(|item| self.eval_expression(item, environment))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Value::Tuple)
+            }
+            Expression::Try(block) => self.eval_try(block, environment),
+            // throw "bad row" or throw e, rethrowing a caught error
+            Expression::Throw(value) => {
+                let error = match self.eval_expression(value, environment)? {
+                    Value::Error(error) => error,
+                    Value::Str(message) => ErrorValue { kind: "Thrown".to_string(), message },
+                    other => return Err(RuntimeError::TypeError(
+                        format!("throw expects a message or an error value, got {}", other)
+                    )),
+                };
+                Err(RuntimeError::Thrown(error))
+            }
+            Expression::Match(scrutinee, arms) => {
+                let value = self.eval_expression(scrutinee, environment)?;
+                self.eval_match(&value, arms, environment)
+            }
+            // {line: 3, width: 1;59}
+            Expression::Map(entries) => {
+                let mut map: Vec<(String, Value)> = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    if map.iter().any(|(existing, _)| existing == key) {
+                        return Err(RuntimeError::TypeError(format!("Duplicate key '{}' in map", key)));
+                    }
+                    map.push((key.clone(), self.eval_expression(value, environment)?));
+                }
+                Ok(Value::Map(map))
+            }
+            // row.width, or e.kind and e.message on a caught error
+            Expression::Field(target, name) => {
+                let target = self.eval_expression(target, environment)?;
+                if let Value::Error(error) = &target {
+                    match name.as_str() {
+                        "kind" => return Ok(Value::Str(error.kind.clone())),
+                        "message" => return Ok(Value::Str(error.message.clone())),
+                        _ => {}
+                    }
+                }
+                target.field(name)
+                    .cloned()
+                    .ok_or_else(|| RuntimeError::MissingField(name.clone(), target.to_string()))
+            }
+            Expression::Index(target, index) => {
+                let target = self.eval_expression(target, environment)?;
+                let index = self.eval_expression(index, environment)?;
+                self.index_value(&target, &index)
+            }
+            // xs[a:b], xs[:b] or xs[a:]
+            Expression::Slice(target, start, end) => {
+                let target = self.eval_expression(target, environment)?;
+                let start = start.as_ref().map(|start| self.eval_expression(start, environment)).transpose()?;
+                let end = end.as_ref().map(|end| self.eval_expression(end, environment)).transpose()?;
+                slice_value(&target, start.as_ref(), end.as_ref())
+            }
+            // |x| x * 2
+            Expression::Lambda(params, body) => Ok(Value::Function(Function::Lambda {
+                params: params.clone(),
+                body: body.clone(),
+                captured: environment.clone(),
+            })),
             Expression::Binary(op, left, right) => {
                 let left_val = self.eval_expression(left, environment)?;
                 let right_val = self.eval_expression(right, environment)?;
@@ -98,20 +672,616 @@ impl Interpreter {
             Expression::Grouped(expr) => {
                 self.eval_expression(expr, environment)
             }
+            Expression::Call(name, args) => {
+                let args = args.iter()
+                    .map(|arg| self.eval_expression(arg, environment))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call_named(name, &args, environment)
+            }
+            // 0..1 step 0;10 or 1..=60
+            Expression::Range(start, end, inclusive, step) => {
+                let start = self.eval_expression(start, environment)?;
+                let end = self.eval_expression(end, environment)?;
+                let step = match step {
+                    Some(step) => self.eval_expression(step, environment)?,
+                    None => Value::Integer(1),
+                };
+                self.range_values(&start, &end, *inclusive, &step).map(Value::List)
+            }
+            // x |> f(a) is f(x, a); x |> f applies any function value to x
+            Expression::Pipeline(input, stage) => {
+                let input = self.eval_expression(input, environment)?;
+                match stage.as_ref() {
+                    Expression::Call(name, args) => {
+                        let mut values = vec![input];
+                        for arg in args {
+                            values.push(self.eval_expression(arg, environment)?);
+                        }
+                        self.call_named(name, &values, environment)
+                    }
+                    _ => {
+                        let function = self.eval_expression(stage, environment)?;
+                        self.apply_function(&function, &[input])
+                    }
+                }
+            }
+            // 3 ninda
+            Expression::Quantity(amount, unit) => {
+                let amount = self.eval_expression(amount, environment)?;
+                if !amount.is_number() {
+                    return Err(RuntimeError::TypeError(
+                        format!("Only numbers can carry a unit, got {} {}", amount, unit)
+                    ));
+                }
+                Ok(Value::Quantity(Quantity::new(amount, self.find_unit(unit)?)))
+            }
+            // x in kuš
+            Expression::Convert(expr, unit) => {
+                let value = self.eval_expression(expr, environment)?;
+                let Value::Quantity(quantity) = &value else {
+                    return Err(RuntimeError::UnitMismatch(
+                        format!("{} has no unit to convert to {}", value, unit)
+                    ));
+                };
+                self.convert_quantity(quantity, self.find_unit(unit)?)
+            }
+        }
+    }
+    
+    fn call_builtin(&self, name: &str, args: &[Value]) -> Result<Value, RuntimeError> {
+        match name {
+            "exact?" => {
+                let [value] = self.expect_args(name, args)?;
+                Ok(Value::Boolean(value.is_exact()))
+            }
+            "inexact?" => {
+                let [value] = self.expect_args(name, args)?;
+                Ok(Value::Boolean(!value.is_exact()))
+            }
+            "floating" => {
+                let [value] = self.expect_args(name, args)?;
+                Ok(Value::Floating(self.to_floating(value)?))
+            }
+            "igi" => {
+                let [value] = self.expect_args(name, args)?;
+                Ok(Value::Floating(self.floating_reciprocal(&self.to_floating(value)?)?))
+            }
+            // anchor(~1,30, 1) = 1;30: the last place counts 60^-places
+            "anchor" => {
+                let [value, places] = self.expect_args(name, args)?;
+                let (Value::Floating(x), Value::Integer(places)) = (value, places) else {
+                    return Err(RuntimeError::TypeError(
+                        format!("anchor expects a floating value and a place count, got {} and {}", value, places)
+                    ));
+                };
+                let overflow = || RuntimeError::Overflow(format!("anchor({}, {})", x, places));
+                let limit = NumericContext::MAX_PLACES;
+                if places.unsigned_abs() > limit as u64 {
+                    return Err(RuntimeError::Overflow(
+                        format!("anchor({}, {}): the place count must be within ±{}", x, places, limit)
+                    ));
+                }
+                if *places < 0 {
+                    let shift = places.unsigned_abs() as u32;
+                    self.check_power(&BigInt::from(60), shift, &Value::Integer(60), &Value::Integer(-places))?;
+                }
+                match x.anchor(*places).ok_or_else(overflow)? {
+                    (scaled, 0) => Ok(Value::from_bigint(scaled)),
+                    (scaled, places) => SexagesimalNum::from_scaled(&scaled, places)
+                        .map(Value::Sexagesimal)
+                        .ok_or_else(overflow),
+                }
+            }
+            // radix(x, 20) or radix(x, 20, 18, 20) rewrites an exact number
+            // in the given radices, most significant first; radix(x) drops it
+            "radix" => {
+                let Some((value, spec)) = args.split_first() else {
+                    return Err(RuntimeError::ArityMismatch(
+                        "radix expects a value and its radices".to_string()
+                    ));
+                };
+                let number = to_radix(value, &[60]).ok_or_else(|| RuntimeError::TypeError(
+                    format!("radix expects an exact number, got {}", value)
+                ))?;
+                if spec.is_empty() {
+                    return Ok(self.without_radix(&Value::Radix(number)));
+                }
+                let radices = spec.iter().rev()
+                    .map(|radix| match radix {
+                        Value::Integer(r) if *r >= 2 => u32::try_from(*r).ok(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<u32>>>()
+                    .ok_or_else(|| RuntimeError::TypeError(
+                        "radices must be integers from 2 to 4294967295".to_string()
+                    ))?;
+                Ok(Value::Radix(number.with_radices(radices)))
+            }
+            // latex(x), or latex(n, d) for the exact quotient with its
+            // repeating places overlined; likewise mathml
+            "latex" | "mathml" => {
+                let places = self.context.get().places;
+                let text = match args {
+                    [value] if name == "latex" => typeset::latex(value, places),
+                    [value] => typeset::mathml(value, places),
+                    [numerator, denominator] => {
+                        let (Some(n), Some(d)) = (numerator.to_bigint(), denominator.to_bigint()) else {
+                            return Err(RuntimeError::TypeError(
+                                format!("{} of a fraction expects integers, got {} and {}", name, numerator, denominator)
+                            ));
+                        };
+                        let fraction = Places::from_fraction(&n, &d).ok_or(RuntimeError::DivisionByZero)?;
+                        if name == "latex" { fraction.to_latex() } else { fraction.to_mathml() }
+                    }
+                    _ => return Err(RuntimeError::ArityMismatch(
+                        format!("{} expects a value or a numerator and denominator, got {} arguments", name, args.len())
+                    )),
+                };
+                Ok(Value::Str(text))
+            }
+            // svg(x): a tablet with x impressed in wedges
+            "svg" => {
+                let [value] = self.expect_args(name, args)?;
+                svg::render(value, self.context.get().places)
+                    .map(Value::Str)
+                    .ok_or_else(|| RuntimeError::TypeError(
+                        format!("svg expects a non-negative number, got {}", value)
+                    ))
+            }
+            "len" => {
+                let [value] = self.expect_args(name, args)?;
+                let len = match value {
+                    Value::Str(s) => s.chars().count(),
+                    Value::List(items) | Value::Tuple(items) => items.len(),
+                    Value::Map(entries) => entries.len(),
+                    _ => return Err(RuntimeError::TypeError(format!("len expects a string, list or map, got {}", value))),
+                };
+                Ok(Value::Integer(len as i64))
+            }
+            // slice(xs, start) or slice(xs, start, end) on a list or a
+            // string; negative positions count from the end
+            "slice" => {
+                let [target, start, rest @ ..] = args else {
+                    return Err(RuntimeError::ArityMismatch(
+                        "slice expects a list or string, a start and an optional end".to_string()
+                    ));
+                };
+                if rest.len() > 1 {
+                    return Err(RuntimeError::ArityMismatch(
+                        format!("slice expects at most 3 arguments, got {}", args.len())
+                    ));
+                }
+                slice_value(target, Some(start), rest.first())
+            }
+            "upper" => {
+                let [value] = self.expect_args(name, args)?;
+                let Value::Str(s) = value else {
+                    return Err(RuntimeError::TypeError(format!("upper expects a string, got {}", value)));
+                };
+                Ok(Value::Str(s.to_uppercase()))
+            }
+            // split(s) splits on whitespace, split(s, sep) on sep
+            "split" => {
+                let parts: Vec<&str> = match args {
+                    [Value::Str(s)] => s.split_whitespace().collect(),
+                    [Value::Str(_), Value::Str(separator)] if separator.is_empty() => {
+                        return Err(RuntimeError::TypeError("split needs a non-empty separator".to_string()));
+                    }
+                    [Value::Str(s), Value::Str(separator)] => s.split(separator.as_str()).collect(),
+                    _ => return Err(RuntimeError::TypeError(
+                        "split expects a string and an optional separator string".to_string()
+                    )),
+                };
+                Ok(Value::List(parts.into_iter().map(|part| Value::Str(part.to_string())).collect()))
+            }
+            // join(items, sep); items that are not strings are written as
+            // they display
+            "join" => {
+                let [items, separator] = self.expect_args(name, args)?;
+                let (Value::List(items), Value::Str(separator)) = (items, separator) else {
+                    return Err(RuntimeError::TypeError(
+                        format!("join expects a list and a separator string, got {} and {}", items, separator)
+                    ));
+                };
+                let parts: Vec<String> = items.iter()
+                    .map(|item| match item {
+                        Value::Str(s) => s.clone(),
+                        _ => item.to_string(),
+                    })
+                    .collect();
+                Ok(Value::Str(parts.join(separator)))
+            }
+            // push(xs, x) returns a new list; lists are values
+            "push" => {
+                let [list, item] = self.expect_args(name, args)?;
+                let mut items = list_items(name, list)?.to_vec();
+                items.push(item.clone());
+                Ok(Value::List(items))
+            }
+            "map" => {
+                let [list, function] = self.expect_args(name, args)?;
+                list_items(name, list)?.iter()
+                    .map(|item| self.apply_function(function, std::slice::from_ref(item)))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Value::List)
+            }
+            "filter" => {
+                let [list, function] = self.expect_args(name, args)?;
+                let mut kept = Vec::new();
+                for item in list_items(name, list)? {
+                    match self.apply_function(function, std::slice::from_ref(item))? {
+                        Value::Boolean(true) => kept.push(item.clone()),
+                        Value::Boolean(false) => {}
+                        other => return Err(RuntimeError::TypeError(
+                            format!("filter expects a function returning true or false, got {}", other)
+                        )),
+                    }
+                }
+                Ok(Value::List(kept))
+            }
+            // reduce(xs, f) starts from the first item, reduce(xs, f, init)
+            // from init
+            "reduce" => {
+                let (list, function, initial) = match args {
+                    [list, function] => (list, function, None),
+                    [list, function, initial] => (list, function, Some(initial.clone())),
+                    _ => return Err(RuntimeError::ArityMismatch(
+                        format!("reduce expects a list, a function and an optional initial value, got {} arguments", args.len())
+                    )),
+                };
+                let mut items = list_items(name, list)?.iter().cloned();
+                let mut accumulator = initial.or_else(|| items.next()).ok_or_else(|| RuntimeError::TypeError(
+                    "reduce of an empty list needs an initial value".to_string()
+                ))?;
+                for item in items {
+                    accumulator = self.apply_function(function, &[accumulator, item])?;
+                }
+                Ok(accumulator)
+            }
+            // The fold starts from the first item rather than 0 or 1, so
+            // that quantities add up in their own unit
+            "sum" | "product" => {
+                let [list] = self.expect_args(name, args)?;
+                let (op, empty) = if name == "sum" { (Operator::Plus, 0) } else { (Operator::Multiply, 1) };
+                let Some((first, rest)) = list_items(name, list)?.split_first() else {
+                    return Ok(Value::Integer(empty));
+                };
+                rest.iter().try_fold(first.clone(), |total, item| self.eval_binary_operation(&op, &total, item))
+            }
+            "sort" => {
+                let [list] = self.expect_args(name, args)?;
+                self.sort_values(list_items(name, list)?).map(Value::List)
+            }
+            // zip(a, b) pairs items up to the shorter list
+            "zip" => {
+                let [left, right] = self.expect_args(name, args)?;
+                let pairs = list_items(name, left)?.iter()
+                    .zip(list_items(name, right)?)
+                    .map(|(a, b)| Value::List(vec![a.clone(), b.clone()]))
+                    .collect();
+                Ok(Value::List(pairs))
+            }
+            // error(kind, message) makes an error value to throw
+            "error" => {
+                let [kind, message] = self.expect_args(name, args)?;
+                let (Value::Str(kind), Value::Str(message)) = (kind, message) else {
+                    return Err(RuntimeError::TypeError(
+                        format!("error expects a kind and a message string, got {} and {}", kind, message)
+                    ));
+                };
+                Ok(Value::Error(ErrorValue { kind: kind.clone(), message: message.clone() }))
+            }
+            "error?" => {
+                let [value] = self.expect_args(name, args)?;
+                Ok(Value::Boolean(matches!(value, Value::Error(_))))
+            }
+            // divmod(a, b) = (a // b, a % b)
+            "divmod" => {
+                let [a, b] = self.expect_args(name, args)?;
+                Ok(Value::Tuple(vec![
+                    self.eval_binary_operation(&Operator::FloorDivide, a, b)?,
+                    self.eval_binary_operation(&Operator::Modulo, a, b)?,
+                ]))
+            }
+            // table(rows): one line per row with the columns right-aligned;
+            // a row that is not a list or tuple is a single column
+            "table" => {
+                let [rows] = self.expect_args(name, args)?;
+                let rows: Vec<Vec<String>> = list_items(name, rows)?.iter()
+                    .map(|row| match row {
+                        Value::List(cells) | Value::Tuple(cells) => cells.iter().map(Value::to_string).collect(),
+                        _ => vec![row.to_string()],
+                    })
+                    .collect();
+                let mut widths: Vec<usize> = Vec::new();
+                for row in &rows {
+                    for (column, cell) in row.iter().enumerate() {
+                        let width = cell.chars().count();
+                        match widths.get_mut(column) {
+                            Some(widest) => *widest = (*widest).max(width),
+                            None => widths.push(width),
+                        }
+                    }
+                }
+                let lines: Vec<String> = rows.iter()
+                    .map(|row| {
+                        let cells: Vec<String> = row.iter()
+                            .zip(&widths)
+                            .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+                            .collect();
+                        cells.join("  ")
+                    })
+                    .collect();
+                Ok(Value::Str(lines.join("\n")))
+            }
+            // Keys, values and [key, value] pairs in the order written
+            "keys" | "values" | "items" => {
+                let [map] = self.expect_args(name, args)?;
+                let Value::Map(entries) = map else {
+                    return Err(RuntimeError::TypeError(format!("{} expects a map, got {}", name, map)));
+                };
+                let items = entries.iter()
+                    .map(|(key, value)| match name {
+                        "keys" => Value::Str(key.clone()),
+                        "values" => value.clone(),
+                        _ => Value::List(vec![Value::Str(key.clone()), value.clone()]),
+                    })
+                    .collect();
+                Ok(Value::List(items))
+            }
+            _ => Err(RuntimeError::UndefinedFunction(name.to_string())),
+        }
+    }
+    
+    /// Calls a function held in a variable, or else the builtin of that name
+    fn call_named(&self, name: &str, args: &[Value], environment: &Environment) -> Result<Value, RuntimeError> {
+        match environment.get(name) {
+            Some(function) => self.apply_function(&function, args),
+            None => self.call_builtin(name, args),
         }
     }
     
+    /// Values from start towards end, adding step each time. Any numbers
+    /// or quantities work, so sexagesimal steps stay exact; a negative
+    /// step counts down.
+    fn range_values(&self, start: &Value, end: &Value, inclusive: bool, step: &Value) -> Result<Vec<Value>, RuntimeError> {
+        let direction = match is_numeric(step).then(|| sign(step)).flatten() {
+            Some(Ordering::Equal) => return Err(RuntimeError::TypeError("A range step cannot be zero".to_string())),
+            Some(Ordering::Greater) => Ordering::Less,
+            Some(Ordering::Less) => Ordering::Greater,
+            None => return Err(RuntimeError::TypeError(format!("A range step must be a number, got {}", step))),
+        };
+        
+        let mut items = Vec::new();
+        let mut current = start.clone();
+        loop {
+            match self.compare_values(&current, end)? {
+                Some(ordering) if ordering == direction || (inclusive && ordering == Ordering::Equal) => {}
+                Some(_) => return Ok(items),
+                None => return Err(RuntimeError::TypeError(format!("Cannot make a range from {} to {}", start, end))),
+            }
+            if items.len() == MAX_RANGE_ITEMS {
+                return Err(RuntimeError::Overflow(
+                    format!("the range {}..{} has more than {} items", start, end, MAX_RANGE_ITEMS)
+                ));
+            }
+            let next = self.eval_binary_operation(&Operator::Plus, &current, step)?;
+            items.push(std::mem::replace(&mut current, next));
+        }
+    }
+    
+    fn apply_function(&self, function: &Value, args: &[Value]) -> Result<Value, RuntimeError> {
+        match function {
+            Value::Function(Function::Builtin(name)) => self.call_builtin(name, args),
+            Value::Function(Function::Constructor { type_name, variant, fields }) => {
+                if fields.len() != args.len() {
+                    return Err(RuntimeError::ArityMismatch(
+                        format!("{} expects {} argument(s), got {}", variant, fields.len(), args.len())
+                    ));
+                }
+                Ok(Value::Record(Record {
+                    type_name: type_name.clone(),
+                    variant: variant.clone(),
+                    fields: fields.iter().cloned().zip(args.iter().cloned()).collect(),
+                }))
+            }
+            Value::Function(Function::Lambda { params, body, captured }) => {
+                if params.len() != args.len() {
+                    return Err(RuntimeError::ArityMismatch(
+                        format!("{} expects {} argument(s), got {}", function, params.len(), args.len())
+                    ));
+                }
+                let mut environment = captured.clone();
+                for (param, arg) in params.iter().zip(args) {
+                    environment.set(param.clone(), arg.clone());
+                }
+                self.eval_expression(body, &mut environment)
+            }
+            _ => Err(RuntimeError::TypeError(format!("{} is not a function", function))),
+        }
+    }
+    
+    /// xs[i] on a list or string, where negative indices count from the
+    /// end, or m["key"] on a map or record
+    fn index_value(&self, target: &Value, index: &Value) -> Result<Value, RuntimeError> {
+        if let (Value::Map(_) | Value::Record(_), Value::Str(key)) = (target, index) {
+            return target.field(key)
+                .cloned()
+                .ok_or_else(|| RuntimeError::MissingField(key.clone(), target.to_string()));
+        }
+        let Value::Integer(i) = index else {
+            return Err(RuntimeError::TypeError(format!("Indices must be integers, got {}", index)));
+        };
+        let out_of_range = |len: usize| RuntimeError::IndexOutOfRange(format!("{} for length {}", i, len));
+        match target {
+            Value::List(items) | Value::Tuple(items) => {
+                let position = sequence_position(items.len(), *i).ok_or_else(|| out_of_range(items.len()))?;
+                Ok(items[position].clone())
+            }
+            Value::Str(s) => {
+                let chars: Vec<char> = s.chars().collect();
+                let position = sequence_position(chars.len(), *i).ok_or_else(|| out_of_range(chars.len()))?;
+                Ok(Value::Str(chars[position].to_string()))
+            }
+            _ => Err(RuntimeError::TypeError(format!("{} cannot be indexed", target))),
+        }
+    }
+    
+    /// Stable sort through compare_values, which can fail, so the
+    /// standard sort is no use: binary insertion instead
+    fn sort_values(&self, items: &[Value]) -> Result<Vec<Value>, RuntimeError> {
+        let mut sorted: Vec<Value> = Vec::with_capacity(items.len());
+        for item in items {
+            // Insert after every item that is not greater
+            let (mut low, mut high) = (0, sorted.len());
+            while low < high {
+                let middle = (low + high) / 2;
+                match self.compare_values(&sorted[middle], item)? {
+                    Some(Ordering::Greater) => high = middle,
+                    Some(_) => low = middle + 1,
+                    None => return Err(RuntimeError::InvalidOperator(
+                        format!("Cannot sort {} with {}", sorted[middle], item)
+                    )),
+                }
+            }
+            sorted.insert(low, item.clone());
+        }
+        Ok(sorted)
+    }
+    
+    fn expect_args<'a, const N: usize>(
+        &self,
+        name: &str,
+        args: &'a [Value],
+    ) -> Result<&'a [Value; N], RuntimeError> {
+        args.try_into().map_err(|_| RuntimeError::ArityMismatch(
+            format!("{} expects {} argument(s), got {}", name, N, args.len())
+        ))
+    }
+    
     fn eval_binary_operation(
         &self, 
         op: &Operator, 
         left: &Value, 
         right: &Value
     ) -> Result<Value, RuntimeError> {
-        match op {
+        if let Some(holds) = comparison(op) {
+            return match self.compare_values(left, right)? {
+                Some(ordering) => Ok(Value::Boolean(holds(ordering))),
+                None if *op == Operator::Equal => Ok(Value::Boolean(false)),
+                None if *op == Operator::NotEqual => Ok(Value::Boolean(true)),
+                None => Err(RuntimeError::InvalidOperator(
+                    format!("Cannot compare {} {} {}", left, operator_symbol(op), right)
+                )),
+            };
+        }
+        
+        if let (Operator::Plus, Value::Str(a), Value::Str(b)) = (op, left, right) {
+            return Ok(Value::Str(format!("{}{}", a, b)));
+        }
+        if let (Operator::Plus, Value::List(a), Value::List(b)) = (op, left, right) {
+            return Ok(Value::List(a.iter().chain(b).cloned().collect()));
+        }
+        
+        if matches!(left, Value::Quantity(_)) || matches!(right, Value::Quantity(_)) {
+            return self.quantity_operation(op, left, right);
+        }
+        
+        if matches!(left, Value::Floating(_)) || matches!(right, Value::Floating(_)) {
+            return self.floating_operation(op, left, right);
+        }
+        
+        // Mixed-radix numbers compute exactly with other exact numbers, and
+        // results are written in the radix of the left-most radix operand
+        if let Some(radices) = radices_of(left).or_else(|| radices_of(right)) {
+            if let (Some(a), Some(b)) = (to_radix(left, &radices), to_radix(right, &radices))
+                && let Some(result) = self.radix_operation(op, &a, &b, left, right)
+            {
+                return result;
+            }
+            return self.eval_binary_operation(op, &self.without_radix(left), &self.without_radix(right));
+        }
+        
+        let result = match op {
             Operator::Plus => self.add_values(left, right),
             Operator::Minus => self.subtract_values(left, right),
             Operator::Multiply => self.multiply_values(left, right),
             Operator::Divide => self.divide_values(left, right),
+            Operator::Power => self.power_values(left, right),
+            Operator::FloorDivide => self.floor_divide_values(left, right),
+            Operator::Modulo => self.modulo_values(left, right),
+            Operator::Equal | Operator::NotEqual | Operator::Less |
+            Operator::LessEqual | Operator::Greater | Operator::GreaterEqual => unreachable!("handled above"),
+        }?;
+        
+        // Strict mode: exact operands must give an exact result
+        if self.context.get().strict && left.is_exact() && right.is_exact() && !result.is_exact() {
+            return Err(RuntimeError::InexactResult(
+                format!("{} {} {} = {}", left, operator_symbol(op), right, result)
+            ));
+        }
+        
+        Ok(result)
+    }
+    
+    /// Orders two values: strings by code point, lists and tuples item by
+    /// item, and numbers by the sign of their difference, so that every
+    /// numeric kind and unit of a dimension compares. None if the values
+    /// have no order, such as a string and a number, or a length and a weight.
+    fn compare_values(&self, left: &Value, right: &Value) -> Result<Option<Ordering>, RuntimeError> {
+        match (left, right) {
+            (Value::Str(a), Value::Str(b)) => Ok(Some(a.cmp(b))),
+            (Value::Boolean(a), Value::Boolean(b)) => Ok(Some(a.cmp(b))),
+            (Value::Integer(a), Value::Integer(b)) => Ok(Some(a.cmp(b))),
+            (Value::List(a), Value::List(b)) | (Value::Tuple(a), Value::Tuple(b)) => {
+                for (x, y) in a.iter().zip(b) {
+                    match self.compare_values(x, y)? {
+                        Some(Ordering::Equal) => continue,
+                        ordering => return Ok(ordering),
+                    }
+                }
+                Ok(Some(a.len().cmp(&b.len())))
+            }
+            // Maps only agree or differ, whatever order their keys were
+            // written in
+            (Value::Map(a), Value::Map(b)) => {
+                if a.len() != b.len() {
+                    return Ok(None);
+                }
+                for (key, value) in a {
+                    let Some(other) = right.field(key) else {
+                        return Ok(None);
+                    };
+                    if self.compare_values(value, other)? != Some(Ordering::Equal) {
+                        return Ok(None);
+                    }
+                }
+                Ok(Some(Ordering::Equal))
+            }
+            (Value::Error(a), Value::Error(b)) => Ok((a == b).then_some(Ordering::Equal)),
+            // Records agree when they are the same variant with equal fields
+            (Value::Record(a), Value::Record(b)) => {
+                if a.type_name != b.type_name || a.variant != b.variant {
+                    return Ok(None);
+                }
+                for ((_, x), (_, y)) in a.fields.iter().zip(&b.fields) {
+                    if self.compare_values(x, y)? != Some(Ordering::Equal) {
+                        return Ok(None);
+                    }
+                }
+                Ok(Some(Ordering::Equal))
+            }
+            // Quantities only compare within a dimension, and never with
+            // plain numbers
+            (Value::Quantity(a), Value::Quantity(b)) if a.unit.dimension != b.unit.dimension => Ok(None),
+            (Value::Quantity(_), other) | (other, Value::Quantity(_)) if !matches!(other, Value::Quantity(_)) => Ok(None),
+            // Floating values have no magnitude; they only agree or differ
+            (Value::Floating(_), _) | (_, Value::Floating(_)) => Ok((left == right).then_some(Ordering::Equal)),
+            _ if is_numeric(left) && is_numeric(right) => {
+                let difference = self.eval_binary_operation(&Operator::Minus, left, right)?;
+                Ok(sign(&difference))
+            }
+            _ => Ok(None),
         }
     }
     
@@ -123,179 +1293,709 @@ impl Interpreter {
         match op {
             Operator::Plus => Ok(value.clone()), // +value
             Operator::Minus => self.negate_value(value),
+            _ => Err(RuntimeError::InvalidOperator(
+                format!("{:?} is not a unary operator", op)
+            )),
         }
     }
     
-    fn add_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+    /// Converts both operands to a common kind. Two integers stay integers;
+    /// otherwise the promotion rule decides between Float and Sexagesimal.
+    fn coerce(&self, left: &Value, right: &Value) -> Option<Operands> {
+        if !left.is_number() || !right.is_number() {
+            return None;
+        }
+        let is_float = |value: &Value| matches!(value, Value::Float(_));
+        let is_sexagesimal = |value: &Value| matches!(value, Value::Sexagesimal(_));
+        let floats = || Operands::Float(left.to_f64(), right.to_f64());
+        
         match (left, right) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a + b)),
-            (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 + b)),
-            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a + *b as f64)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
-            
-            // Sexagesimal operations
-            (Value::Sexagesimal(a), Value::Sexagesimal(b)) => {
-                let result_float = a.to_f64() + b.to_f64();
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
-            }
-            (Value::Sexagesimal(a), Value::Integer(b)) => {
-                let result_float = a.to_f64() + *b as f64;
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
-            }
-            (Value::Integer(a), Value::Sexagesimal(b)) => {
-                let result_float = *a as f64 + b.to_f64();
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
+            (Value::Integer(a), Value::Integer(b)) => Some(Operands::Integer(*a, *b)),
+            (Value::Integer(_) | Value::BigInt(_), Value::Integer(_) | Value::BigInt(_)) => {
+                Some(Operands::BigInt(left.to_bigint()?, right.to_bigint()?))
             }
-            (Value::Sexagesimal(a), Value::Float(b)) => {
-                let result_float = a.to_f64() + b;
-                Ok(Value::Float(result_float))
-            }
-            (Value::Float(a), Value::Sexagesimal(b)) => {
-                let result_float = a + b.to_f64();
-                Ok(Value::Float(result_float))
+            _ => {
+                let sexagesimal_wins = match self.context.get().promotion {
+                    Promotion::Float => !is_float(left) && !is_float(right),
+                    Promotion::Sexagesimal => is_sexagesimal(left) || is_sexagesimal(right),
+                };
+                
+                if !sexagesimal_wins {
+                    return Some(floats());
+                }
+                // Values too large for a sexagesimal fall back to floats
+                match (self.to_sexagesimal(left), self.to_sexagesimal(right)) {
+                    (Some(a), Some(b)) => Some(Operands::Sexagesimal(a, b)),
+                    _ => Some(floats()),
+                }
             }
-            
-            _ => Err(RuntimeError::InvalidOperator(
-                format!("Cannot add {} and {}", left, right)
-            )),
         }
     }
     
-    fn subtract_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
-        match (left, right) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a - b)),
-            (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 - b)),
-            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a - *b as f64)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
-            
-            // Sexagesimal operations
-            (Value::Sexagesimal(a), Value::Sexagesimal(b)) => {
-                let result_float = a.to_f64() - b.to_f64();
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
-            }
-            (Value::Sexagesimal(a), Value::Integer(b)) => {
-                let result_float = a.to_f64() - *b as f64;
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
-            }
-            (Value::Integer(a), Value::Sexagesimal(b)) => {
-                let result_float = *a as f64 - b.to_f64();
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
-            }
-            (Value::Sexagesimal(a), Value::Float(b)) => {
-                let result_float = a.to_f64() - b;
-                Ok(Value::Float(result_float))
-            }
-            (Value::Float(a), Value::Sexagesimal(b)) => {
-                let result_float = a - b.to_f64();
-                Ok(Value::Float(result_float))
+    fn to_sexagesimal(&self, value: &Value) -> Option<SexagesimalNum> {
+        let context = self.context.get();
+        
+        match value {
+            Value::Integer(i) => Some(SexagesimalNum::from_integer(*i)),
+            Value::Radix(r) if r.to_bigint().is_none() => r.to_sexagesimal(context.places, context.rounding),
+            Value::BigInt(_) | Value::Radix(_) => {
+                let n = value.to_bigint()?;
+                let magnitude = n.magnitude().to_u64()?;
+                Some(SexagesimalNum::from_parts(n.is_negative(), magnitude, Vec::new()))
             }
-            
-            _ => Err(RuntimeError::InvalidOperator(
-                format!("Cannot subtract {} from {}", right, left)
+            Value::Float(f) => SexagesimalNum::from_f64_rounded(*f, context.places, context.rounding),
+            Value::Floating(_) | Value::Quantity(_) => None,
+            Value::Boolean(_) | Value::Str(_) | Value::Function(_) | Value::Error(_) => None,
+            Value::List(_) | Value::Tuple(_) | Value::Map(_) | Value::Record(_) => None,
+            Value::Sexagesimal(sex) => Some(sex.clone()),
+        }
+    }
+    
+    /// The fraction kind used when an integer operation has no exact
+    /// integer result, such as 1 / 7 or 2 ^ -1
+    fn inexact_quotient(&self, numerator: &BigInt, denominator: &BigInt) -> Result<Value, RuntimeError> {
+        let context = self.context.get();
+        
+        match context.promotion {
+            Promotion::Float => Ok(Value::Float(
+                numerator.to_f64().unwrap_or(f64::NAN) / denominator.to_f64().unwrap_or(f64::NAN)
             )),
+            Promotion::Sexagesimal => {
+                let numerator = Value::from_bigint(numerator.clone());
+                let denominator = Value::from_bigint(denominator.clone());
+                match (self.to_sexagesimal(&numerator), self.to_sexagesimal(&denominator)) {
+                    (Some(a), Some(b)) => self.sexagesimal_result(
+                        a.checked_div(&b, context.places, context.rounding),
+                        &numerator, "/", &denominator,
+                    ),
+                    _ => Ok(Value::Float(numerator.to_f64() / denominator.to_f64())),
+                }
+            }
         }
     }
     
-    fn multiply_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
-        match (left, right) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a * b)),
-            (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 * b)),
-            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a * *b as f64)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
-            
-            // Sexagesimal operations
-            (Value::Sexagesimal(a), Value::Integer(b)) => {
-                let result_float = a.to_f64() * *b as f64;
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
+    /// Dimension-checked arithmetic. Sums are written in the left unit,
+    /// lengths multiply into sar, and quantities of the same dimension
+    /// divide into plain ratios.
+    fn quantity_operation(&self, op: &Operator, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        let mismatch = || RuntimeError::UnitMismatch(
+            format!("Cannot compute {} {} {}", left, operator_symbol(op), right)
+        );
+        
+        match (op, left, right) {
+            (Operator::Plus | Operator::Minus, Value::Quantity(a), Value::Quantity(b)) => {
+                if a.unit.dimension != b.unit.dimension {
+                    return Err(mismatch());
+                }
+                let b = self.convert_quantity(b, a.unit)?;
+                let Value::Quantity(b) = b else { return Err(mismatch()) };
+                let amount = self.eval_binary_operation(op, &a.amount, &b.amount)?;
+                Ok(Value::Quantity(Quantity::new(amount, a.unit)))
             }
-            (Value::Integer(a), Value::Sexagesimal(b)) => {
-                let result_float = *a as f64 * b.to_f64();
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
+            (Operator::Multiply, Value::Quantity(a), Value::Quantity(b))
+                if a.unit.dimension == Dimension::Length && b.unit.dimension == Dimension::Length =>
+            {
+                let area = self.multiply_values(&self.base_amount(a)?, &self.base_amount(b)?)?;
+                self.quantity_from_base(&area, metrology::area_unit())
             }
-            (Value::Sexagesimal(a), Value::Float(b)) => {
-                let result_float = a.to_f64() * b;
-                Ok(Value::Float(result_float))
+            (Operator::Divide, Value::Quantity(a), Value::Quantity(b)) => {
+                let quotient = self.divide_values(&self.base_amount(a)?, &self.base_amount(b)?)?;
+                match (a.unit.dimension, b.unit.dimension) {
+                    (x, y) if x == y => Ok(quotient),
+                    // An area divided by a length is a length in šu-si
+                    (Dimension::Area, Dimension::Length) => {
+                        let length = Quantity::new(quotient, metrology::find_unit("šu-si").ok_or_else(mismatch)?);
+                        self.convert_quantity(&length, b.unit)
+                    }
+                    _ => Err(mismatch()),
+                }
             }
-            (Value::Float(a), Value::Sexagesimal(b)) => {
-                let result_float = a * b.to_f64();
-                Ok(Value::Float(result_float))
+            (Operator::Multiply | Operator::Divide, Value::Quantity(q), scalar) if scalar.is_number() => {
+                let amount = self.eval_binary_operation(op, &q.amount, scalar)?;
+                Ok(Value::Quantity(Quantity::new(amount, q.unit)))
             }
-            (Value::Sexagesimal(a), Value::Sexagesimal(b)) => {
-                let result_float = a.to_f64() * b.to_f64();
-                Ok(Value::Float(result_float)) // Multiplication of sexagesimals gives float
+            (Operator::Multiply, scalar, Value::Quantity(q)) if scalar.is_number() => {
+                let amount = self.eval_binary_operation(op, scalar, &q.amount)?;
+                Ok(Value::Quantity(Quantity::new(amount, q.unit)))
             }
-            
-            _ => Err(RuntimeError::InvalidOperator(
+            _ => Err(mismatch()),
+        }
+    }
+    
+    fn find_unit(&self, name: &str) -> Result<&'static Unit, RuntimeError> {
+        metrology::find_unit(name).ok_or_else(|| RuntimeError::UnknownUnit(name.to_string()))
+    }
+    
+    fn convert_quantity(&self, quantity: &Quantity, unit: &'static Unit) -> Result<Value, RuntimeError> {
+        let base = self.base_amount(quantity)?;
+        let base = match (quantity.unit.dimension, unit.dimension) {
+            (from, to) if from == to => base,
+            // Hour angle: the sky turns 15 arcsec per second of time
+            (Dimension::Time, Dimension::Angle) => self.multiply_values(&base, &Value::Integer(15))?,
+            (Dimension::Angle, Dimension::Time) => self.divide_values(&base, &Value::Integer(15))?,
+            _ => return Err(RuntimeError::UnitMismatch(format!(
+                "Cannot convert {} ({}) to {} ({})",
+                quantity, quantity.unit.dimension, unit.name, unit.dimension
+            ))),
+        };
+        self.quantity_from_base(&base, unit)
+    }
+    
+    /// The amount counted in the smallest unit of its dimension
+    fn base_amount(&self, quantity: &Quantity) -> Result<Value, RuntimeError> {
+        self.multiply_values(&quantity.amount, &quantity.unit.size_value())
+    }
+    
+    fn quantity_from_base(&self, amount: &Value, unit: &'static Unit) -> Result<Value, RuntimeError> {
+        let amount = self.divide_values(amount, &unit.size_value())?;
+        Ok(Value::Quantity(Quantity::new(amount, unit)))
+    }
+    
+    /// Tablet arithmetic: products, quotients and powers of floating
+    /// values stay floating, while sums need an absolute magnitude
+    fn floating_operation(&self, op: &Operator, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        let base = self.to_floating(left)?;
+        
+        match op {
+            Operator::Multiply => Ok(Value::Floating(base.mul(&self.to_floating(right)?))),
+            Operator::Divide => {
+                self.check_divisor(right)?;
+                let divisor = self.floating_reciprocal(&self.to_floating(right)?)?;
+                Ok(Value::Floating(base.mul(&divisor)))
+            }
+            Operator::Power => {
+                let Value::Integer(exponent) = right else {
+                    return Err(RuntimeError::TypeError(
+                        format!("Floating values can only be raised to integer powers, got {}", right)
+                    ));
+                };
+                let base = if *exponent < 0 { self.floating_reciprocal(&base)? } else { base };
+                let exp = u32::try_from(exponent.unsigned_abs())
+                    .map_err(|_| RuntimeError::Overflow(format!("{} ^ {}", left, right)))?;
+                let mantissa = base.mantissa();
+                self.check_power(&mantissa, exp, left, right)?;
+                Ok(Value::Floating(FloatingNum::from_bigint(&mantissa.pow(exp))))
+            }
+            _ => Err(RuntimeError::InvalidOperator(format!(
+                "{} {} {} needs an absolute magnitude; use anchor(x, places) first",
+                left, operator_symbol(op), right
+            ))),
+        }
+    }
+    
+    /// The places of an exact number, forgetting its magnitude
+    fn to_floating(&self, value: &Value) -> Result<FloatingNum, RuntimeError> {
+        if let Value::Floating(x) = value {
+            return Ok(x.clone());
+        }
+        if let Some(n) = value.to_bigint() {
+            return Ok(FloatingNum::from_bigint(&n));
+        }
+        match self.to_sexagesimal(value) {
+            Some(sex) => Ok(FloatingNum::from_bigint(&sex.to_scaled(sex.places()))),
+            None => Err(RuntimeError::TypeError(format!("{} is not a number", value))),
+        }
+    }
+    
+    fn floating_reciprocal(&self, value: &FloatingNum) -> Result<FloatingNum, RuntimeError> {
+        if value.is_zero() {
+            return Err(RuntimeError::DivisionByZero);
+        }
+        value.reciprocal().ok_or_else(|| RuntimeError::IrregularNumber(value.to_string()))
+    }
+    
+    fn add_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        match self.coerce(left, right) {
+            Some(Operands::Integer(a, b)) => {
+                self.integer_op(a, b, "+", i64::checked_add, |a, b| a + b)
+            }
+            Some(Operands::BigInt(a, b)) => Ok(Value::from_bigint(a + b)),
+            Some(Operands::Float(a, b)) => Ok(Value::Float(a + b)),
+            Some(Operands::Sexagesimal(a, b)) => {
+                self.sexagesimal_result(a.checked_add(&b), left, "+", right)
+            }
+            None => Err(RuntimeError::InvalidOperator(
+                format!("Cannot add {} and {}", left, right)
+            )),
+        }
+    }
+    
+    fn subtract_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        match self.coerce(left, right) {
+            Some(Operands::Integer(a, b)) => {
+                self.integer_op(a, b, "-", i64::checked_sub, |a, b| a - b)
+            }
+            Some(Operands::BigInt(a, b)) => Ok(Value::from_bigint(a - b)),
+            Some(Operands::Float(a, b)) => Ok(Value::Float(a - b)),
+            Some(Operands::Sexagesimal(a, b)) => {
+                self.sexagesimal_result(a.checked_sub(&b), left, "-", right)
+            }
+            None => Err(RuntimeError::InvalidOperator(
+                format!("Cannot subtract {} from {}", right, left)
+            )),
+        }
+    }
+    
+    fn multiply_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        match self.coerce(left, right) {
+            Some(Operands::Integer(a, b)) => {
+                self.integer_op(a, b, "*", i64::checked_mul, |a, b| a * b)
+            }
+            Some(Operands::BigInt(a, b)) => Ok(Value::from_bigint(a * b)),
+            Some(Operands::Float(a, b)) => Ok(Value::Float(a * b)),
+            Some(Operands::Sexagesimal(a, b)) => {
+                self.sexagesimal_result(a.checked_mul(&b), left, "*", right)
+            }
+            None => Err(RuntimeError::InvalidOperator(
                 format!("Cannot multiply {} and {}", left, right)
             )),
         }
     }
     
     fn divide_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
-        // Check for division by zero
-        match right {
-            Value::Integer(0) => return Err(RuntimeError::DivisionByZero),
-            Value::Float(n) if *n == 0.0 => return Err(RuntimeError::DivisionByZero),
-            Value::Sexagesimal(sex) if sex.to_f64() == 0.0 => return Err(RuntimeError::DivisionByZero),
-            _ => {}
-        }
+        self.check_divisor(right)?;
+        let context = self.context.get();
         
-        match (left, right) {
-            (Value::Integer(a), Value::Integer(b)) => {
-                if a % b == 0 {
-                    Ok(Value::Integer(a / b))
+        match self.coerce(left, right) {
+            Some(Operands::Integer(a, b)) => {
+                if a.wrapping_rem(b) == 0 {
+                    self.integer_op(a, b, "/", i64::checked_div, |a, b| a / b)
+                } else {
+                    self.inexact_quotient(&BigInt::from(a), &BigInt::from(b))
+                }
+            }
+            Some(Operands::BigInt(a, b)) => {
+                if (&a % &b).is_zero() {
+                    Ok(Value::from_bigint(a / b))
                 } else {
-                    Ok(Value::Float(*a as f64 / *b as f64))
+                    self.inexact_quotient(&a, &b)
                 }
             }
-            (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 / b)),
-            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a / *b as f64)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a / b)),
-            
-            // Sexagesimal operations
+            Some(Operands::Float(a, b)) => Ok(Value::Float(a / b)),
+            Some(Operands::Sexagesimal(a, b)) => {
+                let quotient = a.checked_div(&b, context.places, context.rounding);
+                self.sexagesimal_result(quotient, left, "/", right)
+            }
+            None => Err(RuntimeError::InvalidOperator(
+                format!("Cannot divide {} by {}", left, right)
+            )),
+        }
+    }
+    
+    fn power_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        // Zero raised to a negative power is a division by zero
+        if let Value::Integer(b) = right
+            && *b < 0
+        {
+            self.check_divisor(left)?;
+        }
+        let context = self.context.get();
+        
+        // Integer exponents are computed exactly; the others are generally
+        // irrational, so they go through floats and are then promoted
+        match (left, right) {
+            (Value::Integer(a), Value::Integer(b)) => match u32::try_from(*b).ok().and_then(|exp| a.checked_pow(exp)) {
+                Some(n) => Ok(Value::Integer(n)),
+                None => self.exact_power(&BigInt::from(*a), *b, left, right),
+            },
+            (Value::BigInt(a), Value::Integer(b)) => self.exact_power(a, *b, left, right),
             (Value::Sexagesimal(a), Value::Integer(b)) => {
-                let result_float = a.to_f64() / *b as f64;
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
+                let power = u32::try_from(b.unsigned_abs()).ok()
+                    .and_then(|exp| a.checked_pow(exp));
+                let power = match power {
+                    Some(power) if *b < 0 => SexagesimalNum::from_integer(1)
+                        .checked_div(&power, context.places, context.rounding),
+                    power => power,
+                };
+                self.sexagesimal_result(power, left, "^", right)
             }
-            (Value::Integer(a), Value::Sexagesimal(b)) => {
-                let result_float = *a as f64 / b.to_f64();
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
+            _ => {
+                let result_float = left.to_f64().powf(right.to_f64());
+                match self.coerce(left, right) {
+                    Some(Operands::Sexagesimal(..)) => {
+                        self.float_to_sexagesimal(result_float, left, "^", right)
+                    }
+                    Some(_) => Ok(Value::Float(result_float)),
+                    None => Err(RuntimeError::InvalidOperator(
+                        format!("Cannot raise {} to the power {}", left, right)
+                    )),
+                }
             }
-            (Value::Sexagesimal(a), Value::Float(b)) => {
-                let result_float = a.to_f64() / b;
-                Ok(Value::Float(result_float))
+        }
+    }
+    
+    /// An integer power beyond i64 arithmetic: negative exponents give
+    /// a quotient, and results too large to compute are refused
+    fn exact_power(&self, base: &BigInt, exp: i64, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        // 0, 1 and -1 stay small whatever the exponent
+        if exp == 0 {
+            return Ok(Value::Integer(1));
+        }
+        if base.is_zero() || base.magnitude().is_one() {
+            let even = exp % 2 == 0;
+            return Ok(Value::from_bigint(if base.is_negative() && even { BigInt::from(1) } else { base.clone() }));
+        }
+        
+        let too_large = || RuntimeError::Overflow(format!("{} ^ {} is too large to compute exactly", left, right));
+        let magnitude = u32::try_from(exp.unsigned_abs()).map_err(|_| too_large())?;
+        self.check_power(base, magnitude, left, right)?;
+        let power = base.pow(magnitude);
+        
+        if exp < 0 {
+            self.inexact_quotient(&BigInt::from(1), &power)
+        } else if matches!(left, Value::Integer(_)) && power.to_i64().is_none() {
+            self.promote_or_overflow(format!("{} ^ {}", left, right), || power)
+        } else {
+            Ok(Value::from_bigint(power))
+        }
+    }
+    
+    fn floor_divide_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        self.check_divisor(right)?;
+        
+        match self.coerce(left, right) {
+            Some(Operands::Integer(a, b)) => {
+                self.integer_op(a, b, "//", checked_floor_div, |a, b| a.div_floor(&b))
             }
-            (Value::Float(a), Value::Sexagesimal(b)) => {
-                let result_float = a / b.to_f64();
-                Ok(Value::Float(result_float))
+            Some(Operands::BigInt(a, b)) => Ok(Value::from_bigint(a.div_floor(&b))),
+            Some(Operands::Float(a, b)) => Ok(Value::Float((a / b).floor())),
+            Some(Operands::Sexagesimal(a, b)) => {
+                let quotient = a.div_rem_floor(&b).map(|(quotient, _)| quotient);
+                self.sexagesimal_result(quotient, left, "//", right)
             }
-            (Value::Sexagesimal(a), Value::Sexagesimal(b)) => {
-                let result_float = a.to_f64() / b.to_f64();
-                Ok(Value::Float(result_float)) // Division of sexagesimals gives float
+            None => Err(RuntimeError::InvalidOperator(
+                format!("Cannot floor-divide {} by {}", left, right)
+            )),
+        }
+    }
+    
+    fn modulo_values(&self, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+        self.check_divisor(right)?;
+        
+        // The result takes the sign of the divisor, matching floor division
+        match self.coerce(left, right) {
+            Some(Operands::Integer(a, b)) => Ok(Value::Integer(floor_rem(a, b))),
+            Some(Operands::BigInt(a, b)) => Ok(Value::from_bigint(a.mod_floor(&b))),
+            Some(Operands::Float(a, b)) => Ok(Value::Float(floor_mod(a, b))),
+            Some(Operands::Sexagesimal(a, b)) => {
+                let remainder = a.div_rem_floor(&b).map(|(_, remainder)| remainder);
+                self.sexagesimal_result(remainder, left, "%", right)
             }
-            
-            _ => Err(RuntimeError::InvalidOperator(
-                format!("Cannot divide {} by {}", left, right)
+            None => Err(RuntimeError::InvalidOperator(
+                format!("Cannot take {} modulo {}", left, right)
             )),
         }
     }
     
+    fn check_divisor(&self, divisor: &Value) -> Result<(), RuntimeError> {
+        match divisor {
+            Value::Integer(0) => Err(RuntimeError::DivisionByZero),
+            Value::BigInt(n) if n.is_zero() => Err(RuntimeError::DivisionByZero),
+            Value::Float(n) if *n == 0.0 => Err(RuntimeError::DivisionByZero),
+            Value::Sexagesimal(sex) if sex.to_f64() == 0.0 => Err(RuntimeError::DivisionByZero),
+            Value::Floating(x) if x.is_zero() => Err(RuntimeError::DivisionByZero),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Applies a checked i64 operation, promoting or failing on overflow
+    fn integer_op(
+        &self,
+        a: i64,
+        b: i64,
+        symbol: &str,
+        checked: fn(i64, i64) -> Option<i64>,
+        promoted: fn(BigInt, BigInt) -> BigInt,
+    ) -> Result<Value, RuntimeError> {
+        match checked(a, b) {
+            Some(result) => Ok(Value::Integer(result)),
+            None => self.promote_or_overflow(
+                format!("{} {} {}", a, symbol, b),
+                || promoted(BigInt::from(a), BigInt::from(b)),
+            ),
+        }
+    }
+    
+    /// Rounds an exact sexagesimal result to the context's places,
+    /// reporting overflow of the integer part instead of wrapping
+    fn sexagesimal_result(
+        &self,
+        result: Option<SexagesimalNum>,
+        left: &Value,
+        symbol: &str,
+        right: &Value,
+    ) -> Result<Value, RuntimeError> {
+        let context = self.context.get();
+        
+        result
+            .and_then(|sex| sex.round_to(context.places, context.rounding))
+            .map(Value::Sexagesimal)
+            .ok_or_else(|| RuntimeError::Overflow(format!("{} {} {}", left, symbol, right)))
+    }
+    
+    fn float_to_sexagesimal(
+        &self,
+        result_float: f64,
+        left: &Value,
+        symbol: &str,
+        right: &Value,
+    ) -> Result<Value, RuntimeError> {
+        match self.to_sexagesimal(&Value::Float(result_float)) {
+            Some(sex) => Ok(Value::Sexagesimal(sex)),
+            None => Err(RuntimeError::Overflow(format!("{} {} {}", left, symbol, right))),
+        }
+    }
+    
+    /// Exact arithmetic on radix numbers; None for powers with a
+    /// fractional exponent, which are generally irrational
+    fn radix_operation(
+        &self,
+        op: &Operator,
+        a: &RadixNum,
+        b: &RadixNum,
+        left: &Value,
+        right: &Value,
+    ) -> Option<Result<Value, RuntimeError>> {
+        let result = match op {
+            Operator::Plus => Ok(a.add(b)),
+            Operator::Minus => Ok(a.sub(b)),
+            Operator::Multiply => Ok(a.mul(b)),
+            Operator::Divide => a.checked_div(b).ok_or(RuntimeError::DivisionByZero),
+            Operator::FloorDivide => a.div_rem_floor(b).map(|(whole, _)| whole).ok_or(RuntimeError::DivisionByZero),
+            Operator::Modulo => a.div_rem_floor(b).map(|(_, rest)| rest).ok_or(RuntimeError::DivisionByZero),
+            Operator::Power => {
+                let exponent = b.to_bigint()?;
+                self.radix_power(a, &exponent, left, right)
+            }
+            Operator::Equal | Operator::NotEqual | Operator::Less |
+            Operator::LessEqual | Operator::Greater | Operator::GreaterEqual => unreachable!("handled above"),
+        };
+        Some(result.map(Value::Radix))
+    }
+    
+    fn radix_power(&self, base: &RadixNum, exponent: &BigInt, left: &Value, right: &Value) -> Result<RadixNum, RuntimeError> {
+        let base = if exponent.is_negative() {
+            RadixNum::from_bigint(&BigInt::from(1), base.radices.clone())
+                .checked_div(base)
+                .ok_or(RuntimeError::DivisionByZero)?
+        } else {
+            base.clone()
+        };
+        let exp = exponent.magnitude().to_u32()
+            .ok_or_else(|| RuntimeError::Overflow(format!("{} ^ {}", left, right)))?;
+        self.check_power(base.numerator(), exp, left, right)?;
+        self.check_power(base.denominator(), exp, left, right)?;
+        Ok(base.pow(exp))
+    }
+    
+    /// A radix number as the kind it stands for: integers stay exact and
+    /// fractions follow the context like other sexagesimal results
+    fn without_radix(&self, value: &Value) -> Value {
+        match value {
+            Value::Radix(r) => match r.to_bigint() {
+                Some(n) => Value::from_bigint(n),
+                None => self.to_sexagesimal(value)
+                    .map(Value::Sexagesimal)
+                    .unwrap_or(Value::Float(r.to_f64())),
+            },
+            _ => value.clone(),
+        }
+    }
+    
+    /// Refuses exact powers too large to compute, instead of exhausting
+    /// time and memory on them
+    fn check_power(&self, base: &BigInt, exp: u32, left: &Value, right: &Value) -> Result<(), RuntimeError> {
+        if power_fits(base, exp) {
+            Ok(())
+        } else {
+            Err(RuntimeError::Overflow(format!("{} ^ {} is too large to compute exactly", left, right)))
+        }
+    }
+    
+    fn promote_or_overflow(
+        &self,
+        expression: String,
+        promoted: impl FnOnce() -> BigInt,
+    ) -> Result<Value, RuntimeError> {
+        match self.context.get().overflow {
+            OverflowMode::Error => Err(RuntimeError::Overflow(expression)),
+            OverflowMode::Promote => Ok(Value::from_bigint(promoted())),
+        }
+    }
+    
     fn negate_value(&self, value: &Value) -> Result<Value, RuntimeError> {
         match value {
-            Value::Integer(n) => Ok(Value::Integer(-n)),
+            Value::Integer(n) => match n.checked_neg() {
+                Some(result) => Ok(Value::Integer(result)),
+                None => self.promote_or_overflow(format!("-({})", n), || -BigInt::from(*n)),
+            },
+            Value::BigInt(n) => Ok(Value::from_bigint(-n)),
             Value::Float(n) => Ok(Value::Float(-n)),
-            Value::Sexagesimal(sex) => {
-                let result_float = -sex.to_f64();
-                Ok(Value::Sexagesimal(SexagesimalNum::from_f64(result_float)))
-            }
+            Value::Sexagesimal(sex) => Ok(Value::Sexagesimal(sex.negated())),
+            Value::Radix(r) => Ok(Value::Radix(r.negated())),
+            Value::Floating(x) => Ok(Value::Floating(x.negated())),
+            Value::Quantity(q) => Ok(Value::Quantity(Quantity::new(self.negate_value(&q.amount)?, q.unit))),
+            Value::Boolean(_) | Value::Str(_) | Value::Function(_) | Value::Error(_) |
+            Value::List(_) | Value::Tuple(_) | Value::Map(_) | Value::Record(_) => Err(RuntimeError::InvalidOperator(
+                format!("Cannot negate {}", value)
+            )),
+        }
+    }
+}
+
+fn radices_of(value: &Value) -> Option<Vec<u32>> {
+    match value {
+        Value::Radix(r) => Some(r.radices.clone()),
+        _ => None,
+    }
+}
+
+/// An exact number as a radix number; other radix numbers keep their own
+/// radices, everything else takes `radices`
+fn to_radix(value: &Value, radices: &[u32]) -> Option<RadixNum> {
+    match value {
+        Value::Radix(r) => Some(r.clone()),
+        Value::Integer(_) | Value::BigInt(_) => Some(RadixNum::from_bigint(&value.to_bigint()?, radices.to_vec())),
+        Value::Sexagesimal(sex) if sex.exact => Some(RadixNum::from_sexagesimal(sex, radices.to_vec())),
+        _ => None,
+    }
+}
+
+/// The test a comparison operator applies to an ordering
+fn comparison(op: &Operator) -> Option<fn(Ordering) -> bool> {
+    match op {
+        Operator::Equal => Some(Ordering::is_eq),
+        Operator::NotEqual => Some(Ordering::is_ne),
+        Operator::Less => Some(Ordering::is_lt),
+        Operator::LessEqual => Some(Ordering::is_le),
+        Operator::Greater => Some(Ordering::is_gt),
+        Operator::GreaterEqual => Some(Ordering::is_ge),
+        _ => None,
+    }
+}
+
+/// Numbers and quantities, which compare through their difference
+fn is_numeric(value: &Value) -> bool {
+    value.is_number() || matches!(value, Value::Quantity(_))
+}
+
+/// Sign of a difference; None for NaN and floating values
+fn sign(value: &Value) -> Option<Ordering> {
+    match value {
+        Value::Quantity(q) => sign(&q.amount),
+        Value::Float(n) => n.partial_cmp(&0.0),
+        Value::Radix(r) => Some(r.numerator().sign().cmp(&Sign::NoSign)),
+        Value::Sexagesimal(sex) => Some(sex.to_scaled(sex.places()).sign().cmp(&Sign::NoSign)),
+        _ => value.to_bigint().map(|n| n.sign().cmp(&Sign::NoSign)),
+    }
+}
+
+fn list_items<'a>(name: &str, value: &'a Value) -> Result<&'a [Value], RuntimeError> {
+    match value {
+        Value::List(items) => Ok(items),
+        _ => Err(RuntimeError::TypeError(format!("{} expects a list, got {}", name, value))),
+    }
+}
+
+/// Position of index `i` among `len` items; negative indices count from
+/// the end. None when out of range.
+fn sequence_position(len: usize, i: i64) -> Option<usize> {
+    let position = if i < 0 { len.checked_sub(i.unsigned_abs() as usize)? } else { i as usize };
+    (position < len).then_some(position)
+}
+
+/// Items or characters from start to end, as for xs[start:end] and
+/// slice(xs, start, end)
+fn slice_value(target: &Value, start: Option<&Value>, end: Option<&Value>) -> Result<Value, RuntimeError> {
+    match target {
+        Value::List(items) => {
+            let (start, end) = slice_bounds(items.len(), start, end)?;
+            Ok(Value::List(items[start..end].to_vec()))
+        }
+        Value::Tuple(items) => {
+            let (start, end) = slice_bounds(items.len(), start, end)?;
+            Ok(Value::Tuple(items[start..end].to_vec()))
         }
+        Value::Str(s) => {
+            let chars: Vec<char> = s.chars().collect();
+            let (start, end) = slice_bounds(chars.len(), start, end)?;
+            Ok(Value::Str(chars[start..end].iter().collect()))
+        }
+        _ => Err(RuntimeError::TypeError(format!("{} cannot be sliced", target))),
+    }
+}
+
+/// Range for a slice: negative positions count from the end, positions
+/// past either end are clamped, and a missing start or end is open
+fn slice_bounds(len: usize, start: Option<&Value>, end: Option<&Value>) -> Result<(usize, usize), RuntimeError> {
+    let position = |value: &Value| match value {
+        Value::Integer(i) if *i < 0 => Ok(len.saturating_sub(i.unsigned_abs() as usize)),
+        Value::Integer(i) => Ok((*i as usize).min(len)),
+        _ => Err(RuntimeError::TypeError(format!("slice positions must be integers, got {}", value))),
+    };
+    let start = match start {
+        Some(start) => position(start)?,
+        None => 0,
+    };
+    let end = match end {
+        Some(end) => position(end)?,
+        None => len,
+    };
+    Ok((start, end.max(start)))
+}
+
+fn operator_symbol(op: &Operator) -> &'static str {
+    match op {
+        Operator::Plus => "+",
+        Operator::Minus => "-",
+        Operator::Multiply => "*",
+        Operator::Divide => "/",
+        Operator::Power => "^",
+        Operator::FloorDivide => "//",
+        Operator::Modulo => "%",
+        Operator::Equal => "==",
+        Operator::NotEqual => "!=",
+        Operator::Less => "<",
+        Operator::LessEqual => "<=",
+        Operator::Greater => ">",
+        Operator::GreaterEqual => ">=",
+    }
+}
+
+/// Integer division rounding towards negative infinity
+fn floor_div(a: i64, b: i64) -> i64 {
+    let quotient = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+fn checked_floor_div(a: i64, b: i64) -> Option<i64> {
+    a.checked_div(b)?;
+    Some(floor_div(a, b))
+}
+
+/// Remainder of floor division; takes the sign of the divisor
+fn floor_rem(a: i64, b: i64) -> i64 {
+    let remainder = a.wrapping_rem(b);
+    if remainder != 0 && (remainder < 0) != (b < 0) {
+        remainder + b
+    } else {
+        remainder
     }
 }
 
+fn floor_mod(a: f64, b: f64) -> f64 {
+    a - b * (a / b).floor()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::{Program, Statement, Expression, Assignment};
+    use crate::ast::{Statement, Expression, Assignment, Destructure, MatchArm, TryCatch, TypeDecl, Variant, WithContext};
+    use crate::value::Rounding;
 
     #[test]
     fn test_eval_integer_arithmetic() {
@@ -359,7 +2059,7 @@ mod tests {
         let result = interpreter.eval_expression(&expr, &mut env).unwrap();
         if let Value::Sexagesimal(sex) = result {
             assert_eq!(sex.integer_part, 2);
-            assert_eq!(sex.fractional_part, 0);
+            assert!(sex.fractional_places.is_empty());
         } else {
             panic!("Expected Sexagesimal result");
         }
@@ -378,7 +2078,7 @@ mod tests {
         let stmt = Statement::Assignment(assign);
         
         let result = interpreter.eval_statement(&stmt, &mut env).unwrap();
-        assert_eq!(result, Value::Integer(42));
+        assert_eq!(result, Some(Value::Integer(42)));
         
         // Verify variable is stored
         let retrieved = env.get("x").unwrap();
@@ -428,4 +2128,1206 @@ mod tests {
         
         assert!(matches!(result, Err(RuntimeError::UndefinedVariable(_))));
     }
+    
+    #[test]
+    fn test_eval_power() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        
+        // Test 2 ^ 10
+        let expr = Expression::Binary(
+            Operator::Power,
+            Box::new(Expression::Number("2".to_string())),
+            Box::new(Expression::Number("10".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Integer(1024));
+        
+        // Test 1;30 ^ 2 = 2;15 (exact)
+        let expr = Expression::Binary(
+            Operator::Power,
+            Box::new(Expression::Number("1;30".to_string())),
+            Box::new(Expression::Number("2".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(2, 15).unwrap()));
+        
+        // Test 0 ^ -1
+        let expr = Expression::Binary(
+            Operator::Power,
+            Box::new(Expression::Number("0".to_string())),
+            Box::new(Expression::Number("-1".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::DivisionByZero)));
+    }
+    
+    #[test]
+    fn test_eval_floor_division_and_modulo() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        
+        // Test -7 // 2 = -4 and -7 % 2 = 1
+        let expr = Expression::Binary(
+            Operator::FloorDivide,
+            Box::new(Expression::Number("-7".to_string())),
+            Box::new(Expression::Number("2".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Integer(-4));
+        
+        let expr = Expression::Binary(
+            Operator::Modulo,
+            Box::new(Expression::Number("-7".to_string())),
+            Box::new(Expression::Number("2".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Integer(1));
+        
+        // Test 2;30 % 1;00 = 0;30
+        let expr = Expression::Binary(
+            Operator::Modulo,
+            Box::new(Expression::Number("2;30".to_string())),
+            Box::new(Expression::Number("1;00".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(0, 30).unwrap()));
+        
+        // Test 5 % 0
+        let expr = Expression::Binary(
+            Operator::Modulo,
+            Box::new(Expression::Number("5".to_string())),
+            Box::new(Expression::Number("0".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::DivisionByZero)));
+    }
+    
+    #[test]
+    fn test_integer_overflow() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        
+        // Test 9223372036854775807 + 1
+        let expr = Expression::Binary(
+            Operator::Plus,
+            Box::new(Expression::Number("9223372036854775807".to_string())),
+            Box::new(Expression::Number("1".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::Overflow(_))));
+        
+        // Test -(-9223372036854775808)
+        let expr = Expression::Unary(
+            Operator::Minus,
+            Box::new(Expression::Number("-9223372036854775808".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::Overflow(_))));
+        
+        // Literals beyond i64 are rejected too, rather than promoted
+        let expr = Expression::Number("9223372036854775808".to_string());
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::Overflow(_))));
+        
+        // Huge powers fail fast instead of exhausting memory
+        let cases = [
+            ("2", "-4000000000"), ("2", "4294967296"), ("2", "-4294967296"),
+            ("0;30", "4000000000"), ("0;1", "4000000000"),
+        ];
+        for (base, exponent) in cases {
+            let expr = Expression::Binary(
+                Operator::Power,
+                Box::new(Expression::Number(base.to_string())),
+                Box::new(Expression::Number(exponent.to_string())),
+            );
+            let result = interpreter.eval_expression(&expr, &mut env);
+            assert!(matches!(result, Err(RuntimeError::Overflow(_))), "{} ^ {}", base, exponent);
+        }
+    }
+    
+    #[test]
+    fn test_integer_overflow_promotion() {
+        let mut env = Environment::new();
+        let mut interpreter = Interpreter::new();
+        let mut context = NumericContext::new();
+        context.overflow = OverflowMode::Promote;
+        interpreter.set_context(context);
+        
+        // Test 9223372036854775807 * 60
+        let expr = Expression::Binary(
+            Operator::Multiply,
+            Box::new(Expression::Number("9223372036854775807".to_string())),
+            Box::new(Expression::Number("60".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(i64::MAX) * 60));
+        
+        // Dividing back down narrows to an Integer again
+        env.set("big".to_string(), result);
+        let expr = Expression::Binary(
+            Operator::Divide,
+            Box::new(Expression::Identifier("big".to_string())),
+            Box::new(Expression::Number("60".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Integer(i64::MAX));
+        
+        // Test 2 ^ 64
+        let expr = Expression::Binary(
+            Operator::Power,
+            Box::new(Expression::Number("2".to_string())),
+            Box::new(Expression::Number("64".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(1u128 << 64)));
+        
+        // Promotion still refuses powers too large to compute
+        let expr = Expression::Binary(
+            Operator::Power,
+            Box::new(Expression::Number("3".to_string())),
+            Box::new(Expression::Number("4000000000".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::Overflow(_))));
+        
+        // 0, 1 and -1 stay exact whatever the exponent
+        let power = |base: &str, exponent: &str| Expression::Binary(
+            Operator::Power,
+            Box::new(Expression::Number(base.to_string())),
+            Box::new(Expression::Number(exponent.to_string())),
+        );
+        for (base, exponent, expected) in [
+            ("1", "4294967296", 1), ("-1", "4294967297", -1), ("-1", "-4294967296", 1), ("0", "4294967296", 0),
+        ] {
+            let result = interpreter.eval_expression(&power(base, exponent), &mut env).unwrap();
+            assert_eq!(result, Value::Integer(expected), "{} ^ {}", base, exponent);
+        }
+        
+        // Big integers with negative exponents follow the promotion mode
+        let mut context = interpreter.context();
+        context.promotion = Promotion::Sexagesimal;
+        interpreter.set_context(context);
+        env.set("big".to_string(), Value::BigInt(BigInt::from(1u64 << 63)));
+        let expr = Expression::Binary(
+            Operator::Power,
+            Box::new(Expression::Identifier("big".to_string())),
+            Box::new(Expression::Number("-1".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert!(matches!(result, Value::Sexagesimal(_)), "{:?}", result);
+    }
+    
+    #[test]
+    fn test_negative_sexagesimal_arithmetic() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let negative = |integer, fractional| {
+            Value::Sexagesimal(SexagesimalNum::from_parts(true, integer, vec![fractional]))
+        };
+        
+        // Test -1;30 + 0 = -1;30 (used to come back as -2;30)
+        let expr = Expression::Binary(
+            Operator::Plus,
+            Box::new(Expression::Number("-1;30".to_string())),
+            Box::new(Expression::Number("0".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, negative(1, 30));
+        assert_eq!(format!("{}", result), "-1;30");
+        
+        // Test 0;30 - 1;00 = -0;30
+        let expr = Expression::Binary(
+            Operator::Minus,
+            Box::new(Expression::Number("0;30".to_string())),
+            Box::new(Expression::Number("1;00".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, negative(0, 30));
+        
+        // Test -1;30 * 3 = -4;30
+        let expr = Expression::Binary(
+            Operator::Multiply,
+            Box::new(Expression::Number("-1;30".to_string())),
+            Box::new(Expression::Number("3".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, negative(4, 30));
+        
+        // Test -1;30 / 2 = -0;45
+        let expr = Expression::Binary(
+            Operator::Divide,
+            Box::new(Expression::Number("-1;30".to_string())),
+            Box::new(Expression::Number("2".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, negative(0, 45));
+        
+        // Test -(1;30) and -(-1;30)
+        let expr = Expression::Unary(
+            Operator::Minus,
+            Box::new(Expression::Number("1;30".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, negative(1, 30));
+        
+        let expr = Expression::Unary(Operator::Minus, Box::new(expr));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(1, 30).unwrap()));
+        
+        // Test -1;30 // 1 = -2 and -1;30 % 1 = 0;30
+        let expr = Expression::Binary(
+            Operator::FloorDivide,
+            Box::new(Expression::Number("-1;30".to_string())),
+            Box::new(Expression::Number("1".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(-2, 0).unwrap()));
+        
+        let expr = Expression::Binary(
+            Operator::Modulo,
+            Box::new(Expression::Number("-1;30".to_string())),
+            Box::new(Expression::Number("1".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(0, 30).unwrap()));
+    }
+    
+    #[test]
+    fn test_promotion_is_consistent() {
+        let mut env = Environment::new();
+        let mut interpreter = Interpreter::new();
+        let binary = |op, left: &str, right: &str| Expression::Binary(
+            op,
+            Box::new(Expression::Number(left.to_string())),
+            Box::new(Expression::Number(right.to_string())),
+        );
+        
+        // Default: Integer < Sexagesimal < Float
+        let result = interpreter.eval_expression(&binary(Operator::Multiply, "1;30", "1;30"), &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(2, 15).unwrap()));
+        
+        let result = interpreter.eval_expression(&binary(Operator::Divide, "1", "1;30"), &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(0, 40).unwrap()));
+        
+        let result = interpreter.eval_expression(&binary(Operator::Plus, "1;30", "0.25"), &mut env).unwrap();
+        assert_eq!(result, Value::Float(1.75));
+        
+        let result = interpreter.eval_expression(&binary(Operator::Divide, "1", "8"), &mut env).unwrap();
+        assert_eq!(result, Value::Float(0.125));
+        
+        // Sexagesimal promotion: Integer < Float < Sexagesimal
+        let mut context = NumericContext::new();
+        context.promotion = Promotion::Sexagesimal;
+        interpreter.set_context(context);
+        
+        let result = interpreter.eval_expression(&binary(Operator::Plus, "1;30", "0.25"), &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(1, 45).unwrap().with_exactness(false)));
+        
+        let result = interpreter.eval_expression(&binary(Operator::Divide, "1", "8"), &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::from_parts(false, 0, vec![7, 30])));
+        
+        let result = interpreter.eval_expression(&binary(Operator::Power, "2", "-2"), &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(0, 15).unwrap()));
+    }
+    
+    #[test]
+    fn test_context_places_and_rounding() {
+        let mut env = Environment::new();
+        let mut interpreter = Interpreter::new();
+        let seventh = Expression::Binary(
+            Operator::Divide,
+            Box::new(Expression::Number("1;00".to_string())),
+            Box::new(Expression::Number("7".to_string())),
+        );
+        
+        let result = interpreter.eval_expression(&seventh, &mut env).unwrap();
+        assert_eq!(format!("{}", result), "≈0;08,34,17,09");
+        
+        let mut context = NumericContext::new();
+        context.places = 2;
+        context.rounding = Rounding::Truncate;
+        interpreter.set_context(context);
+        let result = interpreter.eval_expression(&seventh, &mut env).unwrap();
+        assert_eq!(format!("{}", result), "≈0;08,34");
+        
+        // Exact products are rounded to the context's places too
+        let expr = Expression::Binary(
+            Operator::Multiply,
+            Box::new(Expression::Number("0;00,30".to_string())),
+            Box::new(Expression::Number("0;00,30".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(format!("{}", result), "≈0");
+    }
+    
+    #[test]
+    fn test_eval_with_context() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        
+        // with_context places = 1, rounding = floor { x = 1;00 / 7 }
+        let block = WithContext {
+            settings: vec![
+                ("places".to_string(), Expression::Number("1".to_string())),
+                ("rounding".to_string(), Expression::Identifier("floor".to_string())),
+            ],
+            body: vec![Statement::Assignment(Assignment {
+                variable: "x".to_string(),
+                value: Expression::Binary(
+                    Operator::Divide,
+                    Box::new(Expression::Number("1;00".to_string())),
+                    Box::new(Expression::Number("7".to_string())),
+                ),
+            })],
+        };
+        let result = interpreter.eval_statement(&Statement::WithContext(block), &mut env).unwrap();
+        assert_eq!(result, Some(Value::Sexagesimal(SexagesimalNum::new(0, 8).unwrap().with_exactness(false))));
+        assert_eq!(env.get("x"), result);
+        
+        // The outer context is restored afterwards
+        assert_eq!(interpreter.context(), NumericContext::new());
+        
+        let block = WithContext {
+            settings: vec![("places".to_string(), Expression::Identifier("many".to_string()))],
+            body: vec![Statement::Expression(Expression::Number("1".to_string()))],
+        };
+        let result = interpreter.eval_statement(&Statement::WithContext(block), &mut env);
+        assert!(matches!(result, Err(RuntimeError::ContextError(_))));
+    }
+    
+    #[test]
+    fn test_exactness_builtins() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let exact = |expr: Expression| Expression::Call("exact?".to_string(), vec![expr]);
+        
+        // exact?(1;00 / 8) and exact?(1;00 / 7)
+        let expr = exact(Expression::Binary(
+            Operator::Divide,
+            Box::new(Expression::Number("1;00".to_string())),
+            Box::new(Expression::Number("8".to_string())),
+        ));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Boolean(true));
+        
+        let expr = exact(Expression::Binary(
+            Operator::Divide,
+            Box::new(Expression::Number("1;00".to_string())),
+            Box::new(Expression::Number("7".to_string())),
+        ));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Boolean(false));
+        
+        let expr = exact(Expression::Number("2.5".to_string()));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Boolean(false));
+        
+        let expr = Expression::Call("exact?".to_string(), vec![]);
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::ArityMismatch(_))));
+        
+        let expr = Expression::Call("nope".to_string(), vec![]);
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::UndefinedFunction(_))));
+    }
+    
+    #[test]
+    fn test_strict_mode() {
+        let mut env = Environment::new();
+        let mut interpreter = Interpreter::new();
+        let mut context = NumericContext::new();
+        context.strict = true;
+        interpreter.set_context(context);
+        let divide = |left: &str, right: &str| Expression::Binary(
+            Operator::Divide,
+            Box::new(Expression::Number(left.to_string())),
+            Box::new(Expression::Number(right.to_string())),
+        );
+        
+        let result = interpreter.eval_expression(&divide("1;00", "8"), &mut env).unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::from_parts(false, 0, vec![7, 30])));
+        
+        let result = interpreter.eval_expression(&divide("1;00", "7"), &mut env);
+        assert!(matches!(result, Err(RuntimeError::InexactResult(_))));
+        
+        // 1 / 7 goes through floats under the default promotion
+        let result = interpreter.eval_expression(&divide("1", "7"), &mut env);
+        assert!(matches!(result, Err(RuntimeError::InexactResult(_))));
+        
+        // Operands that are already inexact are not a loss of exactness
+        let result = interpreter.eval_expression(&divide("1.0", "7"), &mut env);
+        assert!(result.is_ok());
+    }
+    
+    #[test]
+    fn test_radix_arithmetic() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        
+        // 20r1,3,7 + 13 = 20r1,4,0
+        let expr = Expression::Binary(
+            Operator::Plus,
+            Box::new(Expression::Number("20r1,3,7".to_string())),
+            Box::new(Expression::Number("13".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result.to_string(), "20r1,4,0");
+        
+        // Fractions keep the radix too, repeating where they must
+        for (left, op, right, expected) in [
+            ("12r1,0", Operator::Divide, "8", "12r1;6"),
+            ("20r1", Operator::Divide, "3", "20r0;(6,13)"),
+            ("12r0;4", Operator::Multiply, "0;30", "12r0;2"),
+            ("12r0;4", Operator::Power, "-2", "12r9"),
+            ("12r7;6", Operator::Modulo, "12r0;4", "12r0;2"),
+        ] {
+            let expr = Expression::Binary(
+                op,
+                Box::new(Expression::Number(left.to_string())),
+                Box::new(Expression::Number(right.to_string())),
+            );
+            let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+            assert_eq!(result.to_string(), expected);
+        }
+        
+        // Floats are not exact, so the radix is dropped
+        let expr = Expression::Binary(
+            Operator::Plus,
+            Box::new(Expression::Number("12r1;6".to_string())),
+            Box::new(Expression::Number("0.25".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Float(1.75));
+        
+        let expr = Expression::Binary(
+            Operator::Less,
+            Box::new(Expression::Number("12r0;4".to_string())),
+            Box::new(Expression::Number("20r0;(6,13)".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Boolean(false));
+        
+        let expr = Expression::Unary(
+            Operator::Minus,
+            Box::new(Expression::Number("12r1,0".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result.to_string(), "-12r1,0");
+    }
+    
+    #[test]
+    fn test_radix_builtin() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let radix = |args: Vec<&str>| Expression::Call(
+            "radix".to_string(),
+            args.into_iter().map(|arg| Expression::Number(arg.to_string())).collect(),
+        );
+        
+        // Day 1386680 of the Maya calendar as a long count
+        let result = interpreter.eval_expression(&radix(vec!["1386680", "20", "18", "20"]), &mut env).unwrap();
+        assert_eq!(result.to_string(), "20_18_20r9,12,11,16,0");
+        
+        let result = interpreter.eval_expression(&radix(vec!["20r1,3,7", "60"]), &mut env).unwrap();
+        assert_eq!(result.to_string(), "60r7,47");
+        
+        let result = interpreter.eval_expression(&radix(vec!["20r1,3,7"]), &mut env).unwrap();
+        assert_eq!(result, Value::Integer(467));
+        
+        // Exact sexagesimals convert exactly; floats are refused
+        let result = interpreter.eval_expression(&radix(vec!["1;30", "20"]), &mut env).unwrap();
+        assert_eq!(result.to_string(), "20r1;10");
+        
+        let result = interpreter.eval_expression(&radix(vec!["20r1;10"]), &mut env).unwrap();
+        assert_eq!(result.to_string(), "1;30");
+        
+        let result = interpreter.eval_expression(&radix(vec!["1.5", "20"]), &mut env);
+        assert!(matches!(result, Err(RuntimeError::TypeError(_))));
+    }
+    
+    #[test]
+    fn test_floating_arithmetic() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let binary = |op: Operator, left: &str, right: &str| Expression::Binary(
+            op,
+            Box::new(Expression::Number(left.to_string())),
+            Box::new(Expression::Number(right.to_string())),
+        );
+        
+        // ~1,30 * ~40 = ~1, the same value as 60 or 1
+        let result = interpreter.eval_expression(&binary(Operator::Multiply, "~1,30", "~40"), &mut env).unwrap();
+        assert_eq!(result.to_string(), "~1");
+        
+        // Division multiplies by igi: ~1 / 8 = ~7,30
+        let result = interpreter.eval_expression(&binary(Operator::Divide, "~1", "8"), &mut env).unwrap();
+        assert_eq!(result.to_string(), "~7,30");
+        
+        let result = interpreter.eval_expression(&binary(Operator::Divide, "~1", "7"), &mut env);
+        assert!(matches!(result, Err(RuntimeError::IrregularNumber(_))));
+        
+        for zero in ["~0", "0"] {
+            let result = interpreter.eval_expression(&binary(Operator::Divide, "~1", zero), &mut env);
+            assert!(matches!(result, Err(RuntimeError::DivisionByZero)));
+        }
+        
+        let result = interpreter.eval_expression(&binary(Operator::Plus, "~1,30", "1"), &mut env);
+        assert!(matches!(result, Err(RuntimeError::InvalidOperator(_))));
+    }
+    
+    #[test]
+    fn test_anchor_builtin() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let anchor = |x: &str, places: &str| Expression::Call(
+            "anchor".to_string(),
+            vec![Expression::Number(x.to_string()), Expression::Number(places.to_string())],
+        );
+        
+        let result = interpreter.eval_expression(&anchor("~1,30", "0"), &mut env).unwrap();
+        assert_eq!(result, Value::Integer(90));
+        
+        let result = interpreter.eval_expression(&anchor("~1,30", "2"), &mut env).unwrap();
+        assert_eq!(result.to_string(), "0;01,30");
+        
+        let result = interpreter.eval_expression(&anchor("~1,30", "-1"), &mut env).unwrap();
+        assert_eq!(result, Value::Integer(5400));
+        
+        // Place counts are bounded before anything is scaled
+        let result = interpreter.eval_expression(&anchor("~1", "100000000000"), &mut env);
+        assert!(matches!(result, Err(RuntimeError::Overflow(_))));
+        let result = interpreter.eval_expression(&anchor("~1", "-4294967295"), &mut env);
+        assert!(matches!(result, Err(RuntimeError::Overflow(_))));
+        
+        // igi(1;04) = ~56,15, whatever the magnitude of 1;04
+        let igi = Expression::Call("igi".to_string(), vec![Expression::Number("1;04".to_string())]);
+        let result = interpreter.eval_expression(&igi, &mut env).unwrap();
+        assert_eq!(result.to_string(), "~56,15");
+    }
+    
+    #[test]
+    fn test_quantity_arithmetic() {
+        let mut env = Environment::new();
+        let mut interpreter = Interpreter::new();
+        let mut context = interpreter.context();
+        context.promotion = Promotion::Sexagesimal;
+        interpreter.set_context(context);
+        let quantity = |amount: &str, unit: &str| Box::new(Expression::Quantity(
+            Box::new(Expression::Number(amount.to_string())),
+            unit.to_string(),
+        ));
+        
+        // 1 ninda + 6 kuš = 1;30 ninda, written in the left unit; fractions
+        // follow the promotion setting
+        let expr = Expression::Binary(Operator::Plus, quantity("1", "ninda"), quantity("6", "kuš"));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result.to_string(), "1;30 ninda");
+        
+        // 30 ninda × 20 ninda = 600 sar = 6 iku
+        let expr = Expression::Binary(Operator::Multiply, quantity("30", "ninda"), quantity("20", "ninda"));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result.to_string(), "600 sar");
+        let expr = Expression::Convert(Box::new(expr), "iku".to_string());
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result.to_string(), "6 iku");
+        
+        // 1 gur / 1 ban is a plain ratio
+        let expr = Expression::Binary(Operator::Divide, quantity("1", "gur"), quantity("1", "ban"));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Integer(30));
+        
+        // 2 × 1 ma-na in gin
+        let expr = Expression::Convert(
+            Box::new(Expression::Binary(
+                Operator::Multiply,
+                Box::new(Expression::Number("2".to_string())),
+                quantity("1", "ma-na"),
+            )),
+            "gin".to_string(),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result.to_string(), "120 gin");
+    }
+    
+    #[test]
+    fn test_unit_mismatch() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let quantity = |amount: &str, unit: &str| Box::new(Expression::Quantity(
+            Box::new(Expression::Number(amount.to_string())),
+            unit.to_string(),
+        ));
+        
+        let expr = Expression::Binary(Operator::Plus, quantity("1", "ninda"), quantity("1", "sila"));
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::UnitMismatch(_))));
+        
+        let expr = Expression::Binary(Operator::Plus, quantity("1", "ninda"), Box::new(Expression::Number("1".to_string())));
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::UnitMismatch(_))));
+        
+        let expr = Expression::Convert(quantity("1", "gin"), "kuš".to_string());
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::UnitMismatch(_))));
+        
+        let result = interpreter.eval_expression(&quantity("1", "furlong"), &mut env);
+        assert!(matches!(result, Err(RuntimeError::UnknownUnit(_))));
+        
+        // Equality across dimensions, or with a plain number, is just false
+        let expr = Expression::Binary(Operator::Equal, quantity("3", "ninda"), quantity("2", "gin"));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Boolean(false));
+        
+        let expr = Expression::Binary(Operator::NotEqual, quantity("3", "ninda"), Box::new(Expression::Number("3".to_string())));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Boolean(true));
+        
+        let expr = Expression::Binary(Operator::Equal, quantity("1", "ninda"), quantity("12", "kuš"));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Boolean(true));
+        
+        let expr = Expression::Binary(Operator::Less, quantity("3", "ninda"), quantity("2", "gin"));
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::InvalidOperator(_))));
+    }
+    
+    #[test]
+    fn test_angle_and_time_conversions() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let convert = |literal: &str, unit: &str| Expression::Convert(
+            Box::new(Expression::Number(literal.to_string())),
+            unit.to_string(),
+        );
+        
+        // Right ascension: 1 h of time is 15° of arc
+        let result = interpreter.eval_expression(&convert("1:02:03.5", "deg"), &mut env).unwrap();
+        assert_eq!(result.to_string(), "15°30'52.5\"");
+        
+        let result = interpreter.eval_expression(&convert("15°30'52.5\"", "h"), &mut env).unwrap();
+        assert_eq!(result.to_string(), "1:02:03.5");
+        
+        let result = interpreter.eval_expression(&convert("1:02:03.5", "s"), &mut env).unwrap();
+        assert_eq!(result.to_string(), "3723;30 s");
+        
+        // Radians are irrational, so they come back inexact
+        let result = interpreter.eval_expression(&convert("180°", "rad"), &mut env).unwrap();
+        let Value::Quantity(radians) = &result else { panic!("expected a quantity") };
+        assert!((radians.amount.to_f64() - std::f64::consts::PI).abs() < 1e-12);
+        assert!(!result.is_exact());
+        
+        let expr = Expression::Binary(
+            Operator::Plus,
+            Box::new(Expression::Number("12°".to_string())),
+            Box::new(Expression::Number("1:00:00".to_string())),
+        );
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::UnitMismatch(_))));
+    }
+    
+    #[test]
+    fn test_typeset_builtins() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let call = |name: &str, literals: &[&str]| Expression::Call(
+            name.to_string(),
+            literals.iter().map(|literal| Expression::Number(literal.to_string())).collect(),
+        );
+        
+        let result = interpreter.eval_expression(&call("latex", &["1;24,51,10"]), &mut env).unwrap();
+        assert_eq!(result, Value::Str("1{;}24{,}51{,}10".to_string()));
+        
+        // 1/7 has no finite expansion; the period is overlined
+        let result = interpreter.eval_expression(&call("latex", &["1", "7"]), &mut env).unwrap();
+        assert_eq!(result.to_string(), "0{;}\\overline{8{,}34{,}17}");
+        
+        let result = interpreter.eval_expression(&call("mathml", &["2"]), &mut env).unwrap();
+        assert!(result.to_string().ends_with("<mn>2</mn></math>"));
+        
+        let result = interpreter.eval_expression(&call("latex", &["1", "0"]), &mut env);
+        assert!(matches!(result, Err(RuntimeError::DivisionByZero)));
+    }
+    
+    #[test]
+    fn test_string_operations() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let text = |s: &str| Box::new(Expression::Str(s.to_string()));
+        
+        let expr = Expression::Binary(Operator::Plus, text("igi "), text("gal₂-bi"));
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Str("igi gal₂-bi".to_string()));
+        
+        let expr = Expression::Binary(Operator::Plus, text("side "), Box::new(Expression::Number("3".to_string())));
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::InvalidOperator(_))));
+        
+        let call = |name: &str, args: Vec<Expression>| Expression::Call(name.to_string(), args);
+        let result = interpreter.eval_expression(&call("len", vec![*text("𒁹𒌍")]), &mut env).unwrap();
+        assert_eq!(result, Value::Integer(2));
+        
+        let slice = call("slice", vec![*text("reciprocal"), Expression::Number("-4".to_string())]);
+        assert_eq!(interpreter.eval_expression(&slice, &mut env).unwrap(), Value::Str("ocal".to_string()));
+        
+        let upper = call("upper", vec![*text("šu-si")]);
+        assert_eq!(interpreter.eval_expression(&upper, &mut env).unwrap(), Value::Str("ŠU-SI".to_string()));
+        
+        let split = call("split", vec![*text("1,24,51,10"), *text(",")]);
+        let parts = interpreter.eval_expression(&split, &mut env).unwrap();
+        assert_eq!(parts.to_string(), "[\"1\", \"24\", \"51\", \"10\"]");
+        
+        env.set("parts".to_string(), parts);
+        let join = call("join", vec![Expression::Identifier("parts".to_string()), *text(" ")]);
+        assert_eq!(interpreter.eval_expression(&join, &mut env).unwrap(), Value::Str("1 24 51 10".to_string()));
+    }
+    
+    #[test]
+    fn test_comparisons() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let compare = |op: Operator, left: Expression, right: Expression| {
+            interpreter.eval_expression(&Expression::Binary(op, Box::new(left), Box::new(right)), &mut Environment::new())
+        };
+        let number = |s: &str| Expression::Number(s.to_string());
+        let text = |s: &str| Expression::Str(s.to_string());
+        
+        assert_eq!(compare(Operator::Less, text("igi"), text("igi-bi")).unwrap(), Value::Boolean(true));
+        assert_eq!(compare(Operator::Equal, number("1;30"), number("1.5")).unwrap(), Value::Boolean(true));
+        assert_eq!(compare(Operator::Greater, number("0;20"), number("0;19,59")).unwrap(), Value::Boolean(true));
+        assert_eq!(compare(Operator::GreaterEqual, number("2"), number("1;59")).unwrap(), Value::Boolean(true));
+        assert_eq!(compare(Operator::NotEqual, text("1"), number("1")).unwrap(), Value::Boolean(true));
+        assert!(matches!(compare(Operator::Less, text("1"), number("1")), Err(RuntimeError::InvalidOperator(_))));
+        
+        // Quantities compare across units of the same dimension
+        let kus = Expression::Quantity(Box::new(number("12")), "kuš".to_string());
+        let ninda = Expression::Quantity(Box::new(number("1")), "ninda".to_string());
+        assert_eq!(compare(Operator::Equal, kus, ninda).unwrap(), Value::Boolean(true));
+        
+        let expr = Expression::Binary(Operator::LessEqual, Box::new(number("3")), Box::new(number("3")));
+        assert_eq!(interpreter.eval_expression(&expr, &mut env).unwrap(), Value::Boolean(true));
+    }
+    
+    #[test]
+    fn test_interpolated_strings() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        env.set("x".to_string(), parse_number("1;24,51,10").unwrap());
+        let field = |spec: &str| FormatPart::Field(
+            Expression::Identifier("x".to_string()),
+            format::parse_spec(spec).unwrap(),
+        );
+        
+        let expr = Expression::Format(vec![
+            FormatPart::Text("side = ".to_string()),
+            field("60.1"),
+            FormatPart::Text(", ".to_string()),
+            field("10.5"),
+            FormatPart::Text(" or ".to_string()),
+            field("60c"),
+        ]);
+        let result = interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(result, Value::Str("side = ≈1;25, 1.41421 or 𒁹;𒎙𒐉 𒐐𒁹 𒌋".to_string()));
+        
+        let expr = Expression::Format(vec![FormatPart::Field(
+            Expression::Str("igi".to_string()),
+            format::parse_spec("60.2").unwrap(),
+        )]);
+        let result = interpreter.eval_expression(&expr, &mut env);
+        assert!(matches!(result, Err(RuntimeError::TypeError(_))));
+    }
+    
+    #[test]
+    fn test_lists() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Expression::Number(s.to_string());
+        let list = Expression::List(vec![number("1;30"), number("2"), number("0;30")]);
+        
+        let xs = interpreter.eval_expression(&list, &mut env).unwrap();
+        assert_eq!(xs.to_string(), "[1;30, 2, 0;30]");
+        env.set("xs".to_string(), xs);
+        let xs = || Box::new(Expression::Identifier("xs".to_string()));
+        
+        let last = Expression::Index(xs(), Box::new(number("-1")));
+        assert_eq!(interpreter.eval_expression(&last, &mut env).unwrap().to_string(), "0;30");
+        let missing = Expression::Index(xs(), Box::new(number("3")));
+        assert!(matches!(interpreter.eval_expression(&missing, &mut env), Err(RuntimeError::IndexOutOfRange(_))));
+        
+        let tail = Expression::Slice(xs(), Some(Box::new(number("1"))), None);
+        assert_eq!(interpreter.eval_expression(&tail, &mut env).unwrap().to_string(), "[2, 0;30]");
+        
+        let call = |name: &str, args: Vec<Expression>| Expression::Call(name.to_string(), args);
+        assert_eq!(interpreter.eval_expression(&call("sum", vec![*xs()]), &mut env).unwrap().to_string(), "4");
+        assert_eq!(interpreter.eval_expression(&call("sort", vec![*xs()]), &mut env).unwrap().to_string(), "[0;30, 1;30, 2]");
+        
+        let pairs = interpreter.eval_expression(&call("zip", vec![*xs(), *xs()]), &mut env).unwrap();
+        assert_eq!(pairs.to_string(), "[[1;30, 1;30], [2, 2], [0;30, 0;30]]");
+        
+        let mixed = Expression::List(vec![number("1"), Expression::Str("a".to_string())]);
+        let result = interpreter.eval_expression(&call("sort", vec![mixed]), &mut env);
+        assert!(matches!(result, Err(RuntimeError::InvalidOperator(_))));
+    }
+    
+    #[test]
+    fn test_higher_order_builtins() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Expression::Number(s.to_string());
+        let x = || Box::new(Expression::Identifier("x".to_string()));
+        let list = Expression::List(vec![number("2"), number("3"), number("4")]);
+        let call = |name: &str, args: Vec<Expression>| Expression::Call(name.to_string(), args);
+        
+        let double = Expression::Lambda(
+            vec!["x".to_string()],
+            Box::new(Expression::Binary(Operator::Multiply, x(), Box::new(number("2")))),
+        );
+        let mapped = interpreter.eval_expression(&call("map", vec![list.clone(), double.clone()]), &mut env).unwrap();
+        assert_eq!(mapped.to_string(), "[4, 6, 8]");
+        
+        let even = Expression::Lambda(
+            vec!["x".to_string()],
+            Box::new(Expression::Binary(
+                Operator::Equal,
+                Box::new(Expression::Binary(Operator::Modulo, x(), Box::new(number("2")))),
+                Box::new(number("0")),
+            )),
+        );
+        let kept = interpreter.eval_expression(&call("filter", vec![list.clone(), even]), &mut env).unwrap();
+        assert_eq!(kept.to_string(), "[2, 4]");
+        
+        // Builtins are values too: the reciprocals of 2, 3 and 4
+        let igi = Expression::Identifier("igi".to_string());
+        let reciprocals = interpreter.eval_expression(&call("map", vec![list.clone(), igi]), &mut env).unwrap();
+        assert_eq!(reciprocals.to_string(), "[~30, ~20, ~15]");
+        
+        let product = interpreter.eval_expression(&call("product", vec![list.clone()]), &mut env).unwrap();
+        assert_eq!(product, Value::Integer(24));
+        
+        // A lambda held in a variable is called by name
+        let function = interpreter.eval_expression(&double, &mut env).unwrap();
+        env.set("double".to_string(), function);
+        let result = interpreter.eval_expression(&call("double", vec![number("0;30")]), &mut env).unwrap();
+        assert_eq!(result.to_string(), "1");
+    }
+    
+    #[test]
+    fn test_maps() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Expression::Number(s.to_string());
+        let row = Expression::Map(vec![
+            ("line".to_string(), number("3")),
+            ("width".to_string(), number("1;59")),
+            ("diagonal".to_string(), number("2;49")),
+        ]);
+        
+        let value = interpreter.eval_expression(&row, &mut env).unwrap();
+        assert_eq!(value.to_string(), "{line: 3, width: 1;59, diagonal: 2;49}");
+        assert_eq!(value.field("line"), Some(&Value::Integer(3)));
+        env.set("row".to_string(), value);
+        let row = || Box::new(Expression::Identifier("row".to_string()));
+        
+        let width = Expression::Field(row(), "width".to_string());
+        assert_eq!(interpreter.eval_expression(&width, &mut env).unwrap().to_string(), "1;59");
+        let by_key = Expression::Index(row(), Box::new(Expression::Str("diagonal".to_string())));
+        assert_eq!(interpreter.eval_expression(&by_key, &mut env).unwrap().to_string(), "2;49");
+        
+        let missing = Expression::Field(row(), "length".to_string());
+        assert!(matches!(interpreter.eval_expression(&missing, &mut env), Err(RuntimeError::MissingField(_, _))));
+        
+        let keys = Expression::Call("keys".to_string(), vec![*row()]);
+        assert_eq!(interpreter.eval_expression(&keys, &mut env).unwrap().to_string(), "[\"line\", \"width\", \"diagonal\"]");
+        
+        // Key order does not matter for equality
+        let reordered = Expression::Map(vec![
+            ("diagonal".to_string(), number("2;49")),
+            ("width".to_string(), number("1;59")),
+            ("line".to_string(), number("3")),
+        ]);
+        let equal = Expression::Binary(Operator::Equal, row(), Box::new(reordered));
+        assert_eq!(interpreter.eval_expression(&equal, &mut env).unwrap(), Value::Boolean(true));
+        
+        let duplicate = Expression::Map(vec![("a".to_string(), number("1")), ("a".to_string(), number("2"))]);
+        assert!(matches!(interpreter.eval_expression(&duplicate, &mut env), Err(RuntimeError::TypeError(_))));
+    }
+    
+    #[test]
+    fn test_destructuring() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Expression::Number(s.to_string());
+        let name = |s: &str| Pattern::Name(s.to_string());
+        
+        // (q, r) = divmod(7;30, 2)
+        let statement = Statement::Destructure(Destructure {
+            pattern: Pattern::Tuple(vec![name("q"), name("r")]),
+            value: Expression::Call("divmod".to_string(), vec![number("7;30"), number("2")]),
+        });
+        let result = interpreter.eval_statement(&statement, &mut env).unwrap().unwrap();
+        assert_eq!(result.to_string(), "(3, 1;30)");
+        assert_eq!(env.get("q").unwrap().to_string(), "3");
+        assert_eq!(env.get("r").unwrap().to_string(), "1;30");
+        
+        // [w, [_, d]] = [1;59, [3, 2;49]]
+        let statement = Statement::Destructure(Destructure {
+            pattern: Pattern::List(vec![name("w"), Pattern::List(vec![Pattern::Wildcard, name("d")])]),
+            value: Expression::List(vec![number("1;59"), Expression::List(vec![number("3"), number("2;49")])]),
+        });
+        interpreter.eval_statement(&statement, &mut env).unwrap();
+        assert_eq!(env.get("w").unwrap().to_string(), "1;59");
+        assert_eq!(env.get("d").unwrap().to_string(), "2;49");
+        
+        // Shapes must agree, and nothing is bound when they do not
+        let statement = Statement::Destructure(Destructure {
+            pattern: Pattern::Tuple(vec![name("a"), name("b")]),
+            value: Expression::Tuple(vec![number("1"), number("2"), number("3")]),
+        });
+        let result = interpreter.eval_statement(&statement, &mut env);
+        assert!(matches!(result, Err(RuntimeError::ShapeMismatch(_))));
+        assert_eq!(env.get("a"), None);
+        
+        let statement = Statement::Destructure(Destructure {
+            pattern: Pattern::List(vec![name("a"), name("b")]),
+            value: Expression::Tuple(vec![number("1"), number("2")]),
+        });
+        let result = interpreter.eval_statement(&statement, &mut env);
+        assert!(matches!(result, Err(RuntimeError::ShapeMismatch(_))));
+    }
+    
+    /// type Shape = Square(side) | Rect(width, length) | Empty
+    fn declare_shape(interpreter: &Interpreter, env: &mut Environment) {
+        let variant = |name: &str, fields: &[&str]| Variant {
+            name: name.to_string(),
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+        };
+        let decl = TypeDecl {
+            name: "Shape".to_string(),
+            variants: vec![variant("Square", &["side"]), variant("Rect", &["width", "length"]), variant("Empty", &[])],
+        };
+        let result = interpreter.eval_statement(&Statement::TypeDecl(decl), env).unwrap();
+        assert_eq!(result, None);
+    }
+    
+    #[test]
+    fn test_type_declarations() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Expression::Number(s.to_string());
+        declare_shape(&interpreter, &mut env);
+        
+        let rect = Expression::Call("Rect".to_string(), vec![number("1;59"), number("3")]);
+        let value = interpreter.eval_expression(&rect, &mut env).unwrap();
+        assert_eq!(value.to_string(), "Rect(width: 1;59, length: 3)");
+        env.set("r".to_string(), value);
+        
+        let width = Expression::Field(Box::new(Expression::Identifier("r".to_string())), "width".to_string());
+        assert_eq!(interpreter.eval_expression(&width, &mut env).unwrap().to_string(), "1;59");
+        
+        let empty = interpreter.eval_expression(&Expression::Identifier("Empty".to_string()), &mut env).unwrap();
+        assert_eq!(empty.to_string(), "Empty");
+        
+        let square = Expression::Call("Square".to_string(), vec![]);
+        assert!(matches!(interpreter.eval_expression(&square, &mut env), Err(RuntimeError::ArityMismatch(_))));
+    }
+    
+    #[test]
+    fn test_match() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Expression::Number(s.to_string());
+        let name = |s: &str| Expression::Identifier(s.to_string());
+        let arm = |pattern: Pattern, guard: Option<Expression>, body: Expression| MatchArm { pattern, guard, body };
+        declare_shape(&interpreter, &mut env);
+        
+        // match shape { Square(s) => s * s, Rect(w, l) => w * l, Empty => 0 }
+        let area = |shape: Expression, arms: Vec<MatchArm>| Expression::Match(Box::new(shape), arms);
+        let arms = vec![
+            arm(Pattern::Variant("Square".to_string(), vec![Pattern::Name("s".to_string())]), None,
+                Expression::Binary(Operator::Multiply, Box::new(name("s")), Box::new(name("s")))),
+            arm(Pattern::Variant("Rect".to_string(), vec![Pattern::Name("w".to_string()), Pattern::Name("l".to_string())]), None,
+                Expression::Binary(Operator::Multiply, Box::new(name("w")), Box::new(name("l")))),
+            arm(Pattern::Name("Empty".to_string()), None, number("0")),
+        ];
+        let rect = Expression::Call("Rect".to_string(), vec![number("0;30"), number("4")]);
+        let result = interpreter.eval_expression(&area(rect, arms.clone()), &mut env).unwrap();
+        assert_eq!(result.to_string(), "2");
+        let result = interpreter.eval_expression(&area(name("Empty"), arms.clone()), &mut env).unwrap();
+        assert_eq!(result, Value::Integer(0));
+        
+        // Leaving out Empty is caught even when the value is a Square
+        let square = Expression::Call("Square".to_string(), vec![number("2")]);
+        let result = interpreter.eval_expression(&area(square, arms[..2].to_vec()), &mut env);
+        assert!(matches!(result, Err(RuntimeError::NonExhaustiveMatch(_))));
+        
+        // Sexagesimal literals and guards: match x { 0;30 => "half", n if n > 1 => "big", _ => "small" }
+        let arms = vec![
+            arm(Pattern::Literal(number("0;30")), None, Expression::Str("half".to_string())),
+            arm(Pattern::Name("n".to_string()),
+                Some(Expression::Binary(Operator::Greater, Box::new(name("n")), Box::new(number("1")))),
+                Expression::Str("big".to_string())),
+            arm(Pattern::Wildcard, None, Expression::Str("small".to_string())),
+        ];
+        for (x, expected) in [("0.5", "half"), ("1;00,01", "big"), ("0;59", "small")] {
+            let result = interpreter.eval_expression(&area(number(x), arms.clone()), &mut env).unwrap();
+            assert_eq!(result, Value::Str(expected.to_string()));
+        }
+        
+        let result = interpreter.eval_expression(&area(number("3"), arms[..1].to_vec()), &mut env);
+        assert!(matches!(result, Err(RuntimeError::NonExhaustiveMatch(_))));
+        
+        // Pattern names are local to the arm, but other assignments in it
+        // are kept: match Square(2) { Square(s) => try { total = s * s } catch e { 0 } }
+        env.set("s".to_string(), Value::Str("outer".to_string()));
+        let body = Expression::Try(TryCatch {
+            body: vec![Statement::Assignment(Assignment {
+                variable: "total".to_string(),
+                value: Expression::Binary(Operator::Multiply, Box::new(name("s")), Box::new(name("s"))),
+            })],
+            error: "e".to_string(),
+            handler: vec![Statement::Expression(number("0"))],
+        });
+        let arms = vec![
+            arm(Pattern::Variant("Square".to_string(), vec![Pattern::Name("s".to_string())]), None, body),
+            arm(Pattern::Wildcard, None, number("0")),
+        ];
+        let square = Expression::Call("Square".to_string(), vec![number("2")]);
+        let result = interpreter.eval_expression(&area(square, arms), &mut env).unwrap();
+        assert_eq!(result, Value::Integer(4));
+        assert_eq!(env.get("total"), Some(Value::Integer(4)));
+        assert_eq!(env.get("s"), Some(Value::Str("outer".to_string())));
+    }
+    
+    #[test]
+    fn test_ranges() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Box::new(Expression::Number(s.to_string()));
+        let range = |start: &str, end: &str, inclusive: bool, step: Option<&str>| {
+            Expression::Range(number(start), number(end), inclusive, step.map(number))
+        };
+        
+        let result = interpreter.eval_expression(&range("1", "5", false, None), &mut env).unwrap();
+        assert_eq!(result.to_string(), "[1, 2, 3, 4]");
+        let result = interpreter.eval_expression(&range("1", "5", true, None), &mut env).unwrap();
+        assert_eq!(result.to_string(), "[1, 2, 3, 4, 5]");
+        
+        // Sexagesimal steps stay exact, so the end is reached exactly
+        let result = interpreter.eval_expression(&range("0", "1", true, Some("0;10")), &mut env).unwrap();
+        assert_eq!(result.to_string(), "[0, 0;10, 0;20, 0;30, 0;40, 0;50, 1]");
+        
+        let result = interpreter.eval_expression(&range("3", "0", false, Some("-1")), &mut env).unwrap();
+        assert_eq!(result.to_string(), "[3, 2, 1]");
+        let result = interpreter.eval_expression(&range("5", "1", false, None), &mut env).unwrap();
+        assert_eq!(result, Value::List(vec![]));
+        
+        let result = interpreter.eval_expression(&range("0", "1", false, Some("0")), &mut env);
+        assert!(matches!(result, Err(RuntimeError::TypeError(_))));
+        let result = interpreter.eval_expression(&range("0", "10000000", false, None), &mut env);
+        assert!(matches!(result, Err(RuntimeError::Overflow(_))));
+    }
+    
+    #[test]
+    fn test_pipelines() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Box::new(Expression::Number(s.to_string()));
+        let pipe = |input: Expression, stage: Expression| Expression::Pipeline(Box::new(input), Box::new(stage));
+        
+        // zip(xs, xs |> map(igi)) |> table
+        let numbers = Expression::List(vec![*number("2"), *number("3"), *number("4"), *number("5")]);
+        let zipped = pipe(numbers.clone(), Expression::Call("map".to_string(), vec![Expression::Identifier("igi".to_string())]));
+        let zipped = Expression::Call("zip".to_string(), vec![numbers, zipped]);
+        let table = pipe(zipped, Expression::Identifier("table".to_string()));
+        let result = interpreter.eval_expression(&table, &mut env).unwrap();
+        assert_eq!(result, Value::Str("2  ~30\n3  ~20\n4  ~15\n5  ~12".to_string()));
+        
+        // A lambda as a stage: 1;30 |> |x| x * 2
+        let double = Expression::Lambda(
+            vec!["x".to_string()],
+            Box::new(Expression::Binary(Operator::Multiply, Box::new(Expression::Identifier("x".to_string())), number("2"))),
+        );
+        let result = interpreter.eval_expression(&pipe(*number("1;30"), double), &mut env).unwrap();
+        assert_eq!(result.to_string(), "3");
+    }
+    
+    #[test]
+    fn test_try_catch() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let number = |s: &str| Expression::Number(s.to_string());
+        let field = |name: &str| Expression::Field(Box::new(Expression::Identifier("e".to_string())), name.to_string());
+        let try_catch = |body: Vec<Statement>, handler: Expression| Expression::Try(TryCatch {
+            body,
+            error: "e".to_string(),
+            handler: vec![Statement::Expression(handler)],
+        });
+        
+        // try { x = 2; igi(7) } catch e { e.kind }
+        let expr = try_catch(
+            vec![
+                Statement::Assignment(Assignment { variable: "x".to_string(), value: number("2") }),
+                Statement::Expression(Expression::Call("igi".to_string(), vec![number("7")])),
+            ],
+            field("kind"),
+        );
+        assert_eq!(interpreter.eval_expression(&expr, &mut env).unwrap(), Value::Str("IrregularNumber".to_string()));
+        assert_eq!(env.get("x"), Some(Value::Integer(2)));
+        
+        // e is gone after the handler, but its assignments stay:
+        // try { 1 / 0 } catch e { y = e.kind }
+        let expr = Expression::Try(TryCatch {
+            body: vec![Statement::Expression(Expression::Binary(
+                Operator::Divide,
+                Box::new(number("1")),
+                Box::new(number("0")),
+            ))],
+            error: "e".to_string(),
+            handler: vec![Statement::Assignment(Assignment { variable: "y".to_string(), value: field("kind") })],
+        });
+        interpreter.eval_expression(&expr, &mut env).unwrap();
+        assert_eq!(env.get("y"), Some(Value::Str("DivisionByZero".to_string())));
+        assert_eq!(env.get("e"), None);
+        
+        // A thrown message keeps its text; error() sets the kind
+        let thrown = Expression::Throw(Box::new(Expression::Str("bad row".to_string())));
+        let expr = try_catch(vec![Statement::Expression(thrown.clone())], field("message"));
+        assert_eq!(interpreter.eval_expression(&expr, &mut env).unwrap(), Value::Str("bad row".to_string()));
+        
+        let custom = Expression::Call("error".to_string(), vec![Expression::Str("BadRow".to_string()), Expression::Str("line 3".to_string())]);
+        let expr = try_catch(vec![Statement::Expression(Expression::Throw(Box::new(custom)))], field("kind"));
+        assert_eq!(interpreter.eval_expression(&expr, &mut env).unwrap(), Value::Str("BadRow".to_string()));
+        
+        // Uncaught errors still stop evaluation
+        let result = interpreter.eval_expression(&thrown, &mut env);
+        assert!(matches!(result, Err(RuntimeError::Thrown(error)) if error.kind == "Thrown"));
+    }
+    
+    #[test]
+    fn test_skipping_failed_rows() {
+        let mut env = Environment::new();
+        let interpreter = Interpreter::new();
+        let n = || Expression::Identifier("n".to_string());
+        
+        // map(xs, |n| try { igi(n) } catch e { e }), then drop the errors
+        let reciprocal = Expression::Lambda(vec!["n".to_string()], Box::new(Expression::Try(TryCatch {
+            body: vec![Statement::Expression(Expression::Call("igi".to_string(), vec![n()]))],
+            error: "e".to_string(),
+            handler: vec![Statement::Expression(Expression::Identifier("e".to_string()))],
+        })));
+        let numbers = ["2", "7", "3"].iter().map(|s| Expression::Number(s.to_string())).collect();
+        let results = Expression::Call("map".to_string(), vec![Expression::List(numbers), reciprocal]);
+        let results = interpreter.eval_expression(&results, &mut env).unwrap();
+        let Value::List(items) = &results else { panic!("expected a list") };
+        assert!(matches!(&items[1], Value::Error(error) if error.kind == "IrregularNumber"));
+        
+        env.set("results".to_string(), results);
+        let regular = Expression::Lambda(vec!["n".to_string()], Box::new(Expression::Binary(
+            Operator::Equal,
+            Box::new(Expression::Call("error?".to_string(), vec![n()])),
+            Box::new(Expression::Identifier("false".to_string())),
+        )));
+        let kept = Expression::Call("filter".to_string(), vec![Expression::Identifier("results".to_string()), regular]);
+        assert_eq!(interpreter.eval_expression(&kept, &mut env).unwrap().to_string(), "[~30, ~20]");
+    }
 }
\ No newline at end of file
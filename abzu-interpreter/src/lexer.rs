@@ -1,10 +1,39 @@
-use crate::token::Token;
+use crate::cuneiform;
+use crate::metrology;
+use crate::format;
+use crate::token::{Comment, CommentKind, Span, TemplatePart, Token};
+use crate::value;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum LexerError {
     #[error("Unexpected character: '{0}' at position {1}")]
     UnexpectedCharacter(char, usize),
+    #[error("Invalid cuneiform number at position {0}: every place must be below 60")]
+    InvalidCuneiformNumber(usize),
+    #[error("Unterminated block comment starting at position {0}")]
+    UnterminatedComment(usize),
+    #[error("Unterminated string starting at position {0}")]
+    UnterminatedString(usize),
+    #[error("Invalid escape sequence at position {0}")]
+    InvalidEscape(usize),
+    #[error("Invalid interpolated string at position {0}: {1}")]
+    InvalidFormatString(usize, String),
+}
+
+impl LexerError {
+    /// The same error with its position moved by `by`, for input lexed
+    /// on its own, such as an interpolated field
+    fn offset(self, by: usize) -> Self {
+        match self {
+            LexerError::UnexpectedCharacter(ch, position) => LexerError::UnexpectedCharacter(ch, position + by),
+            LexerError::InvalidCuneiformNumber(position) => LexerError::InvalidCuneiformNumber(position + by),
+            LexerError::UnterminatedComment(position) => LexerError::UnterminatedComment(position + by),
+            LexerError::UnterminatedString(position) => LexerError::UnterminatedString(position + by),
+            LexerError::InvalidEscape(position) => LexerError::InvalidEscape(position + by),
+            LexerError::InvalidFormatString(position, reason) => LexerError::InvalidFormatString(position + by, reason),
+        }
+    }
 }
 
 pub struct Lexer {
@@ -12,6 +41,8 @@ pub struct Lexer {
     position: usize,
     read_position: usize,
     ch: char,
+    /// Comments met while tokenizing, in source order
+    comments: Vec<Comment>,
 }
 
 impl Lexer {
@@ -21,6 +52,7 @@ impl Lexer {
             position: 0,
             read_position: 0,
             ch: '\0',
+            comments: Vec::new(),
         };
         lexer.read_char();
         lexer
@@ -50,14 +82,290 @@ impl Lexer {
         }
     }
     
+    /// Comments found by the last call to `tokenize`
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+    
+    fn push_comment(&mut self, kind: CommentKind, start: usize) {
+        let text = self.input[start..self.position].iter().collect();
+        self.comments.push(Comment { kind, text, span: Span { start, end: self.position } });
+    }
+    
+    /// Reads a `#` comment up to the end of the line; the newline itself
+    /// is still a token
+    fn read_line_comment(&mut self) {
+        let start = self.position;
+        while self.ch != '\n' && self.ch != '\0' {
+            self.read_char();
+        }
+        self.push_comment(CommentKind::Line, start);
+    }
+    
+    /// Reads a `/* ... */` comment, in which nested pairs must balance
+    fn read_block_comment(&mut self) -> Result<(), LexerError> {
+        let start = self.position;
+        let mut depth = 0;
+        loop {
+            match (self.ch, self.peek_char()) {
+                ('\0', _) => return Err(LexerError::UnterminatedComment(start)),
+                ('/', '*') => {
+                    depth += 1;
+                    self.read_char();
+                }
+                ('*', '/') => {
+                    depth -= 1;
+                    self.read_char();
+                }
+                _ => {}
+            }
+            self.read_char();
+            if depth == 0 {
+                break;
+            }
+        }
+        self.push_comment(CommentKind::Block, start);
+        Ok(())
+    }
+    
+    /// Reads a double-quoted string, resolving the escapes \n, \t, \r,
+    /// \0, \\, \" and \u{12079} for any code point
+    fn read_string(&mut self) -> Result<String, LexerError> {
+        let start = self.position;
+        self.read_char();
+        
+        let mut text = String::new();
+        loop {
+            match self.ch {
+                '"' => {
+                    self.read_char();
+                    return Ok(text);
+                }
+                '\0' => return Err(LexerError::UnterminatedString(start)),
+                '\\' => text.push(self.read_escape()?),
+                ch => {
+                    text.push(ch);
+                    self.read_char();
+                }
+            }
+        }
+    }
+    
+    /// Reads an escape sequence from its backslash onwards
+    fn read_escape(&mut self) -> Result<char, LexerError> {
+        let position = self.position;
+        self.read_char();
+        let ch = match self.ch {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' | '"' => self.ch,
+            'u' => self.read_unicode_escape(position)?,
+            _ => return Err(LexerError::InvalidEscape(position)),
+        };
+        self.read_char();
+        Ok(ch)
+    }
+    
+    /// Reads an interpolated string, f"side = {x:60.3}". The text takes
+    /// the same escapes as strings, with {{ and }} for literal braces.
+    fn read_format_string(&mut self) -> Result<Vec<TemplatePart>, LexerError> {
+        let start = self.position;
+        self.read_char();
+        self.read_char();
+        
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        loop {
+            match self.ch {
+                '"' => {
+                    self.read_char();
+                    break;
+                }
+                '\0' => return Err(LexerError::UnterminatedString(start)),
+                '\\' => text.push(self.read_escape()?),
+                '{' | '}' if self.peek_char() == self.ch => {
+                    text.push(self.ch);
+                    self.read_char();
+                    self.read_char();
+                }
+                '}' => {
+                    return Err(LexerError::InvalidFormatString(self.position, "unmatched '}'".to_string()));
+                }
+                '{' => {
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(self.read_format_field()?);
+                }
+                ch => {
+                    text.push(ch);
+                    self.read_char();
+                }
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(parts)
+    }
+    
+    /// Reads a {expression:spec} field. The spec starts at the first ':'
+    /// outside brackets and strings, so a time literal must be written as
+    /// ({1:30:00}).
+    fn read_format_field(&mut self) -> Result<TemplatePart, LexerError> {
+        let start = self.position;
+        let unclosed = || LexerError::InvalidFormatString(start, "unclosed '{'".to_string());
+        self.read_char();
+        
+        let expression_start = self.position;
+        let mut depth = 0;
+        while depth > 0 || !matches!(self.ch, ':' | '}') {
+            match self.ch {
+                '\0' => return Err(unclosed()),
+                // Strings may hold brackets and colons of their own
+                '"' => {
+                    self.read_char();
+                    while self.ch != '"' {
+                        match self.ch {
+                            '\0' => return Err(unclosed()),
+                            '\\' => self.read_char(),
+                            _ => {}
+                        }
+                        self.read_char();
+                    }
+                }
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+            self.read_char();
+        }
+        let expression: String = self.input[expression_start..self.position].iter().collect();
+        
+        let mut spec = format::FormatSpec::default();
+        if self.ch == ':' {
+            self.read_char();
+            let spec_start = self.position;
+            while self.ch != '}' {
+                if matches!(self.ch, '\0' | '"') {
+                    return Err(unclosed());
+                }
+                self.read_char();
+            }
+            let text: String = self.input[spec_start..self.position].iter().collect();
+            spec = format::parse_spec(&text)
+                .map_err(|e| LexerError::InvalidFormatString(spec_start, e.to_string()))?;
+        }
+        self.read_char();
+        
+        if expression.trim().is_empty() {
+            return Err(LexerError::InvalidFormatString(start, "empty field".to_string()));
+        }
+        let tokens = Lexer::new(&expression).tokenize()
+            .map_err(|e| e.offset(expression_start))?;
+        Ok(TemplatePart::Field(tokens, spec))
+    }
+    
+    /// Reads the {hex} part of a \u escape, stopping on the closing brace
+    fn read_unicode_escape(&mut self, position: usize) -> Result<char, LexerError> {
+        self.read_char();
+        if self.ch != '{' {
+            return Err(LexerError::InvalidEscape(position));
+        }
+        self.read_char();
+        let digits_start = self.position;
+        while self.ch.is_ascii_hexdigit() {
+            self.read_char();
+        }
+        let digits: String = self.input[digits_start..self.position].iter().collect();
+        if self.ch != '}' || digits.is_empty() || digits.len() > 6 {
+            return Err(LexerError::InvalidEscape(position));
+        }
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(LexerError::InvalidEscape(position))
+    }
+    
     fn read_identifier(&mut self) -> String {
         let position = self.position;
         while self.ch.is_alphabetic() || self.ch == '_' || self.ch.is_ascii_digit() {
             self.read_char();
         }
+        // Predicates such as exact? end in a question mark
+        if self.ch == '?' {
+            self.read_char();
+        }
+        
+        // Unit names written with hyphens or dots: ma-na, ŠU.SI
+        loop {
+            let name: String = self.input[position..self.position].iter().collect();
+            if !matches!(self.ch, '-' | '.') || !self.peek_char().is_alphabetic() {
+                return name;
+            }
+            let end = (self.read_position..self.input.len())
+                .find(|i| !self.input[*i].is_alphabetic())
+                .unwrap_or(self.input.len());
+            let joined: String = self.input[position..end].iter().collect();
+            if metrology::find_compound_unit(&joined).is_none() {
+                return name;
+            }
+            while self.position < end {
+                self.read_char();
+            }
+        }
+    }
+    
+    /// Reads the rest of 12°30'15" after the degrees; minutes and
+    /// seconds are optional, and seconds may have decimals
+    fn read_angle(&mut self, position: usize) -> String {
+        self.read_char(); // consume the degree sign
+        
+        for marks in [['\'', '′'], ['"', '″']] {
+            let end = (self.position..self.input.len())
+                .find(|i| !self.input[*i].is_ascii_digit() && self.input[*i] != '.')
+                .unwrap_or(self.input.len());
+            if end > self.position && self.input.get(end).is_some_and(|ch| marks.contains(ch)) {
+                while self.position <= end {
+                    self.read_char();
+                }
+            }
+        }
+        
+        self.input[position..self.position].iter().collect()
+    }
+    
+    /// True at the ':' of h:mm:ss
+    fn time_follows(&self) -> bool {
+        (0..6).all(|offset| {
+            self.input.get(self.position + offset).is_some_and(|ch| {
+                if offset % 3 == 0 { *ch == ':' } else { ch.is_ascii_digit() }
+            })
+        })
+    }
+    
+    /// Reads the rest of 1:02:03.5 after the hours
+    fn read_time(&mut self, position: usize) -> String {
+        for _ in 0..6 {
+            self.read_char();
+        }
+        if self.ch == '.' && self.peek_char().is_ascii_digit() {
+            self.read_char();
+            while self.ch.is_ascii_digit() {
+                self.read_char();
+            }
+        }
+        
         self.input[position..self.position].iter().collect()
     }
     
+    /// Reads a numeric literal. ',' and ';' are place separators only
+    /// when a digit follows directly: 1,30 and 1;30 are numbers, while in
+    /// [1, 30] the comma separates items and in `x = 1; y = 2` the
+    /// semicolon separates statements. Spaced separators never made a
+    /// valid number, so scripts that lexed before lex the same way.
     fn read_number(&mut self) -> String {
         let position = self.position;
         
@@ -66,21 +374,180 @@ impl Lexer {
             self.read_char();
         }
         
-        // Check for decimal point (base-10) or semicolon (sexagesimal)
-        if self.ch == '.' || self.ch == ';' || self.ch == ',' {
+        // Angles and times: 12°30'15" and 1:02:03.5
+        if self.ch == '°' {
+            return self.read_angle(position);
+        }
+        if self.time_follows() {
+            return self.read_time(position);
+        }
+        
+        // Radix literals: 20r1,3,7, 20_18_20r9,12,11,16,0 or 20r0;(6,13)
+        if self.ch == '_' || (self.ch == 'r' && self.peek_char().is_ascii_digit()) {
+            while self.ch == '_' || self.ch.is_ascii_digit() {
+                self.read_char();
+            }
+            if self.ch == 'r' {
+                self.read_char();
+            }
+            // A repeating period in parentheses ends the literal
+            let mut previous = 'r';
+            let mut repeating = false;
+            loop {
+                let next = self.peek_char();
+                let continues = match self.ch {
+                    ch if ch.is_ascii_digit() => true,
+                    ',' | ';' => next.is_ascii_digit() || (next == '(' && !repeating),
+                    '(' => matches!(previous, ',' | ';') && next.is_ascii_digit(),
+                    ')' => repeating,
+                    _ => false,
+                };
+                if !continues {
+                    break;
+                }
+                repeating |= self.ch == '(';
+                previous = self.ch;
+                self.read_char();
+                if previous == ')' {
+                    break;
+                }
+            }
+            return self.input[position..self.position].iter().collect();
+        }
+        
+        // Check for decimal point (base-10) or semicolon (sexagesimal); 1..5
+        // is a range, not 1. followed by .5
+        if (self.ch == '.' && self.peek_char() != '.') || (matches!(self.ch, ';' | ',') && self.peek_char().is_ascii_digit()) {
+            let separator = self.ch;
             self.read_char(); // consume the separator
             
             // Read fractional part
             while self.ch.is_ascii_digit() {
                 self.read_char();
             }
+            
+            // Further places after ';' or ',' (e.g. 1;24,51,10)
+            while separator != '.' && self.ch == ',' && self.peek_char().is_ascii_digit() {
+                self.read_char();
+                while self.ch.is_ascii_digit() {
+                    self.read_char();
+                }
+            }
+        }
+        
+        self.input[position..self.position].iter().collect()
+    }
+    
+    /// True if a numeral sign follows the current character, skipping
+    /// the spaces scribes leave between places
+    fn numeral_follows(&self) -> bool {
+        self.input[self.read_position.min(self.input.len())..].iter()
+            .find(|ch| **ch != ' ')
+            .is_some_and(|ch| cuneiform::is_numeral(*ch))
+    }
+    
+    /// Reads a cuneiform number and returns it as an ordinary literal.
+    /// Signs within a place add up; places are separated by spaces or a
+    /// ',' with no space after it, and ';' starts the fractional places,
+    /// so 𒁹 𒌍 is 90 and 𒁹;𒌍 is 1;30.
+    fn read_cuneiform_number(&mut self) -> Result<String, LexerError> {
+        let position = self.position;
+        let mut integer_places = vec![0];
+        let mut fractional_places: Vec<u64> = Vec::new();
+        let mut in_fraction = false;
+        
+        loop {
+            if let Some(value) = cuneiform::numeral_value(self.ch) {
+                let places = if in_fraction { &mut fractional_places } else { &mut integer_places };
+                *places.last_mut().unwrap() += value;
+                self.read_char();
+            } else if self.ch == ' ' && self.numeral_follows() {
+                while self.ch == ' ' {
+                    self.read_char();
+                }
+                if in_fraction { fractional_places.push(0) } else { integer_places.push(0) }
+            } else if self.ch == ',' && cuneiform::is_numeral(self.peek_char()) {
+                // As with digits, a ',' followed by a space separates list items
+                self.read_char();
+                if in_fraction { fractional_places.push(0) } else { integer_places.push(0) }
+            } else if self.ch == ';' && !in_fraction && cuneiform::is_numeral(self.peek_char()) {
+                self.read_char();
+                in_fraction = true;
+                fractional_places.push(0);
+            } else {
+                break;
+            }
+        }
+        
+        cuneiform::literal_from_places(&integer_places, &fractional_places)
+            .ok_or(LexerError::InvalidCuneiformNumber(position))
+    }
+    
+    /// End of the transliterated sign starting at `start`, such as
+    /// `2(u)` or `1/2(diš)`, or None if there is no such sign
+    fn transliteration_at(&self, start: usize) -> Option<usize> {
+        let digits_from = |from: usize| {
+            (from..self.input.len()).find(|i| !self.input[*i].is_ascii_digit()).unwrap_or(self.input.len())
+        };
+        
+        let mut end = digits_from(start);
+        if end == start {
+            return None;
+        }
+        if self.input.get(end) == Some(&'/') {
+            let numerator_end = end;
+            end = digits_from(end + 1);
+            if end == numerator_end + 1 {
+                return None;
+            }
+        }
+        if self.input.get(end) != Some(&'(') {
+            return None;
+        }
+        
+        let close = end + self.input[end..].iter().position(|ch| *ch == ')')?;
+        let name: String = self.input[end + 1..close].iter().collect();
+        value::transliteration_unit(&name).map(|_| close + 1)
+    }
+    
+    /// Reads a run of transliterated signs, 1(diš) 2(u) 3(diš), which may
+    /// contain a ';' before the fractional places
+    fn read_transliteration(&mut self) -> String {
+        let position = self.position;
+        let mut end = self.transliteration_at(position).unwrap_or(position);
+        let skip_spaces = |mut i: usize| {
+            while self.input.get(i) == Some(&' ') {
+                i += 1;
+            }
+            i
+        };
+        
+        loop {
+            let mut next = skip_spaces(end);
+            if self.input.get(next) == Some(&';') {
+                // The place separator is written 1(diš);3(u) or, as in ATF,
+                // 1(diš) ; 3(u). Attached to a sign and followed by a space,
+                // as in 1(diš); 2(u), it ends a statement instead.
+                if next == end && self.input.get(next + 1) == Some(&' ') {
+                    break;
+                }
+                next = skip_spaces(next + 1);
+            }
+            match self.transliteration_at(next) {
+                Some(sign_end) => end = sign_end,
+                None => break,
+            }
         }
         
+        while self.position < end {
+            self.read_char();
+        }
         self.input[position..self.position].iter().collect()
     }
     
     pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
         let mut tokens = Vec::new();
+        self.comments.clear();
         
         while self.ch != '\0' {
             match self.ch {
@@ -103,30 +570,108 @@ impl Lexer {
                 }
                 '-' => {
                     // Check if this is a negative number or subtraction
-                    if self.peek_char().is_ascii_digit() && 
+                    let next = self.peek_char();
+                    if (next.is_ascii_digit() || cuneiform::is_numeral(next)) && 
                        (tokens.is_empty() || 
-                        matches!(tokens.last(), Some(Token::Plus | Token::Minus | Token::Asterisk | Token::Slash | Token::Assign | Token::LParen))) {
+                        matches!(tokens.last(), Some(Token::Plus | Token::Minus | Token::Asterisk | Token::Slash |
+                                                     Token::Caret | Token::DoubleAsterisk | Token::DoubleSlash |
+                                                     Token::Percent | Token::Assign | Token::LParen |
+                                                     Token::Equal | Token::NotEqual | Token::Less |
+                                                     Token::LessEqual | Token::Greater | Token::GreaterEqual |
+                                                     Token::LBracket | Token::Comma | Token::Colon | Token::Pipe |
+                                                     Token::Semicolon | Token::LBrace | Token::FatArrow |
+                                                     Token::Match | Token::If | Token::DotDot |
+                                                     Token::DotDotEqual | Token::Step | Token::Throw))) {
                         // It's a negative number, let read_number handle it
-                        let num = self.read_number();
+                        let num = if self.transliteration_at(self.read_position).is_some() {
+                            self.read_char();
+                            format!("-{}", self.read_transliteration())
+                        } else if next.is_ascii_digit() {
+                            self.read_number()
+                        } else {
+                            self.read_char();
+                            format!("-{}", self.read_cuneiform_number()?)
+                        };
                         tokens.push(Token::Number(num));
                     } else {
                         tokens.push(Token::Minus);
                         self.read_char();
                     }
                 }
+                // Comments: # to the end of the line, and /* ... */ blocks.
+                // // stays floor division.
+                '#' => self.read_line_comment(),
+                '/' if self.peek_char() == '*' => self.read_block_comment()?,
+                
                 '*' => {
-                    tokens.push(Token::Asterisk);
+                    if self.peek_char() == '*' {
+                        self.read_char();
+                        tokens.push(Token::DoubleAsterisk);
+                    } else {
+                        tokens.push(Token::Asterisk);
+                    }
                     self.read_char();
                 }
                 '/' => {
-                    tokens.push(Token::Slash);
+                    if self.peek_char() == '/' {
+                        self.read_char();
+                        tokens.push(Token::DoubleSlash);
+                    } else {
+                        tokens.push(Token::Slash);
+                    }
+                    self.read_char();
+                }
+                '^' => {
+                    tokens.push(Token::Caret);
+                    self.read_char();
+                }
+                '%' => {
+                    tokens.push(Token::Percent);
+                    self.read_char();
+                }
+                // Floating sexagesimal literals: ~1,30
+                '~' if self.peek_char().is_ascii_digit() => {
+                    let position = self.position;
                     self.read_char();
+                    while self.ch.is_ascii_digit() || (self.ch == ',' && self.peek_char().is_ascii_digit()) {
+                        self.read_char();
+                    }
+                    tokens.push(Token::Number(self.input[position..self.position].iter().collect()));
                 }
                 '=' => {
-                    tokens.push(Token::Assign);
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        tokens.push(Token::Equal);
+                    } else if self.peek_char() == '>' {
+                        self.read_char();
+                        tokens.push(Token::FatArrow);
+                    } else {
+                        tokens.push(Token::Assign);
+                    }
+                    self.read_char();
+                }
+                '!' if self.peek_char() == '=' => {
+                    self.read_char();
+                    self.read_char();
+                    tokens.push(Token::NotEqual);
+                }
+                '<' | '>' => {
+                    let less = self.ch == '<';
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        tokens.push(if less { Token::LessEqual } else { Token::GreaterEqual });
+                    } else {
+                        tokens.push(if less { Token::Less } else { Token::Greater });
+                    }
                     self.read_char();
                 }
                 
+                // String literals
+                '"' => {
+                    let text = self.read_string()?;
+                    tokens.push(Token::Str(text));
+                }
+                
                 // Parentheses
                 '(' => {
                     tokens.push(Token::LParen);
@@ -137,17 +682,93 @@ impl Lexer {
                     self.read_char();
                 }
                 
-                // Number separators (handled in read_number)
-                '.' | ';' | ',' => {
-                    // These should be consumed as part of number reading
-                    // If we encounter them here, it's an error
-                    return Err(LexerError::UnexpectedCharacter(self.ch, self.position));
+                '[' => {
+                    tokens.push(Token::LBracket);
+                    self.read_char();
+                }
+                ']' => {
+                    tokens.push(Token::RBracket);
+                    self.read_char();
+                }
+                ',' => {
+                    tokens.push(Token::Comma);
+                    self.read_char();
+                }
+                ':' => {
+                    tokens.push(Token::Colon);
+                    self.read_char();
+                }
+                '|' if self.peek_char() == '>' => {
+                    self.read_char();
+                    self.read_char();
+                    tokens.push(Token::PipeForward);
+                }
+                '|' => {
+                    tokens.push(Token::Pipe);
+                    self.read_char();
+                }
+                
+                ';' => {
+                    tokens.push(Token::Semicolon);
+                    self.read_char();
+                }
+                '{' => {
+                    tokens.push(Token::LBrace);
+                    self.read_char();
+                }
+                '}' => {
+                    tokens.push(Token::RBrace);
+                    self.read_char();
+                }
+                '.' if self.peek_char() == '.' => {
+                    self.read_char();
+                    self.read_char();
+                    if self.ch == '=' {
+                        self.read_char();
+                        tokens.push(Token::DotDotEqual);
+                    } else {
+                        tokens.push(Token::DotDot);
+                    }
+                }
+                // A '.' outside a number is field access: row.width
+                '.' => {
+                    tokens.push(Token::Dot);
+                    self.read_char();
+                }
+                
+                // Cuneiform numerals, checked before identifiers since the
+                // signs are alphabetic
+                ch if cuneiform::is_numeral(ch) => {
+                    let num = self.read_cuneiform_number()?;
+                    tokens.push(Token::Number(num));
+                }
+                
+                // Interpolated strings: f"side = {x:60.3}"
+                'f' if self.peek_char() == '"' => {
+                    let parts = self.read_format_string()?;
+                    tokens.push(Token::FormatStr(parts));
                 }
                 
                 // Identifiers (start with letter or underscore)
                 ch if ch.is_alphabetic() || ch == '_' => {
                     let ident = self.read_identifier();
-                    tokens.push(Token::Identifier(ident));
+                    tokens.push(match ident.as_str() {
+                        "in" => Token::In,
+                        "type" => Token::Type,
+                        "match" => Token::Match,
+                        "if" => Token::If,
+                        "step" => Token::Step,
+                        "try" => Token::Try,
+                        "catch" => Token::Catch,
+                        "throw" => Token::Throw,
+                        _ => Token::Identifier(ident),
+                    });
+                }
+                
+                // Transliterated numbers: 1(diš) 2(u)
+                ch if ch.is_ascii_digit() && self.transliteration_at(self.position).is_some() => {
+                    let num = self.read_transliteration();
+                    tokens.push(Token::Number(num));
                 }
                 
                 // Numbers (including negative and with separators)
@@ -235,4 +856,468 @@ mod tests {
             Token::EOF,
         ]);
     }
+    
+    #[test]
+    fn test_power_and_division_operators() {
+        let input = "2 ^ 3 ** 2 // 5 % -4";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("2".to_string()),
+            Token::Caret,
+            Token::Number("3".to_string()),
+            Token::DoubleAsterisk,
+            Token::Number("2".to_string()),
+            Token::DoubleSlash,
+            Token::Number("5".to_string()),
+            Token::Percent,
+            Token::Number("-4".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_multi_place_sexagesimal() {
+        let input = "1;24,51,10 * 2";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("1;24,51,10".to_string()),
+            Token::Asterisk,
+            Token::Number("2".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_predicate_call() {
+        let input = "exact?(x)";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Identifier("exact?".to_string()),
+            Token::LParen,
+            Token::Identifier("x".to_string()),
+            Token::RParen,
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_cuneiform_numerals() {
+        let input = "𒌋𒁹 + 𒁹 𒌍 * 𒁹;𒌍 - 𒐕𒌍";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("11".to_string()),
+            Token::Plus,
+            Token::Number("90".to_string()),
+            Token::Asterisk,
+            Token::Number("1;30".to_string()),
+            Token::Minus,
+            Token::Number("90".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_cuneiform_fraction_places() {
+        let input = "x = -𒁹,𒌋;𒌍 𒐋";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Identifier("x".to_string()),
+            Token::Assign,
+            Token::Number("-70;30,6".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_cuneiform_separators_need_no_space() {
+        let mut lexer = Lexer::new("[𒁹, 𒌍]; 𒁹; 𒌍");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::LBracket,
+            Token::Number("1".to_string()),
+            Token::Comma,
+            Token::Number("30".to_string()),
+            Token::RBracket,
+            Token::Semicolon,
+            Token::Number("1".to_string()),
+            Token::Semicolon,
+            Token::Number("30".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_invalid_cuneiform_place() {
+        let mut lexer = Lexer::new("𒌍𒌍 𒁹");
+        assert!(matches!(lexer.tokenize(), Err(LexerError::InvalidCuneiformNumber(0))));
+    }
+    
+    #[test]
+    fn test_transliterated_numbers() {
+        let input = "1(diš) 2(u) 3(diš) * -1(diš) ; 3(u) + f(2)";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("1(diš) 2(u) 3(diš)".to_string()),
+            Token::Asterisk,
+            Token::Number("-1(diš) ; 3(u)".to_string()),
+            Token::Plus,
+            Token::Identifier("f".to_string()),
+            Token::LParen,
+            Token::Number("2".to_string()),
+            Token::RParen,
+            Token::EOF,
+        ]);
+        
+        // A ';' followed by a space only joins when spaced on both sides
+        let mut lexer = Lexer::new("1(diš); 2(u)\n1(diš);2(u)");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("1(diš)".to_string()),
+            Token::Semicolon,
+            Token::Number("2(u)".to_string()),
+            Token::Newline,
+            Token::Number("1(diš);2(u)".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_radix_literals() {
+        let input = "20r1,3,7 + 20_18_20r9,12,11,16,0";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("20r1,3,7".to_string()),
+            Token::Plus,
+            Token::Number("20_18_20r9,12,11,16,0".to_string()),
+            Token::EOF,
+        ]);
+        
+        // Fractional places, with a repeating period that ends the literal
+        let input = "f(12r1;6, 20r0;(6,13))";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens[2], Token::Number("12r1;6".to_string()));
+        assert_eq!(tokens[3], Token::Comma);
+        assert_eq!(tokens[4], Token::Number("20r0;(6,13)".to_string()));
+        assert_eq!(tokens[5], Token::RParen);
+    }
+    
+    #[test]
+    fn test_floating_literals() {
+        let input = "~1,30 * ~40";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("~1,30".to_string()),
+            Token::Asterisk,
+            Token::Number("~40".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_units_and_conversion() {
+        let input = "3 ma-na + 2 ŠU.SI in kuš";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("3".to_string()),
+            Token::Identifier("ma-na".to_string()),
+            Token::Plus,
+            Token::Number("2".to_string()),
+            Token::Identifier("ŠU.SI".to_string()),
+            Token::In,
+            Token::Identifier("kuš".to_string()),
+            Token::EOF,
+        ]);
+        
+        // Hyphens only join known unit names
+        let mut lexer = Lexer::new("a-b");
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens[1], Token::Minus);
+        
+        // ...that are hyphenated themselves: uš and s are units, but u-s is
+        // a subtraction and s.h a field access
+        let mut lexer = Lexer::new("u-s");
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens[1], Token::Minus);
+        let mut lexer = Lexer::new("s.h");
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Identifier("s".to_string()));
+        assert_eq!(tokens[1], Token::Dot);
+    }
+    
+    #[test]
+    fn test_angle_and_time_literals() {
+        let input = "12°30'15\" + 1:02:03.5 in deg";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("12°30'15\"".to_string()),
+            Token::Plus,
+            Token::Number("1:02:03.5".to_string()),
+            Token::In,
+            Token::Identifier("deg".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_comments_are_trivia() {
+        let input = "x = 7 // 2 # floor division\n/* outer /* nested */ still outer */ x";
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Identifier("x".to_string()),
+            Token::Assign,
+            Token::Number("7".to_string()),
+            Token::DoubleSlash,
+            Token::Number("2".to_string()),
+            Token::Newline,
+            Token::Identifier("x".to_string()),
+            Token::EOF,
+        ]);
+        
+        let comments = lexer.comments();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].kind, CommentKind::Line);
+        assert_eq!(comments[0].text, "# floor division");
+        assert_eq!(comments[0].span, Span { start: 11, end: 27 });
+        assert_eq!(comments[1].kind, CommentKind::Block);
+        assert_eq!(comments[1].text, "/* outer /* nested */ still outer */");
+        assert_eq!(comments[1].span.start, 28);
+    }
+    
+    #[test]
+    fn test_unterminated_block_comment() {
+        let mut lexer = Lexer::new("1 /* open /* closed */");
+        assert!(matches!(lexer.tokenize(), Err(LexerError::UnterminatedComment(2))));
+    }
+    
+    #[test]
+    fn test_string_literals() {
+        let input = r#"label = "sign \u{12079}\t\"diš\"" == name"#;
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Identifier("label".to_string()),
+            Token::Assign,
+            Token::Str("sign 𒁹\t\"diš\"".to_string()),
+            Token::Equal,
+            Token::Identifier("name".to_string()),
+            Token::EOF,
+        ]);
+        
+        assert!(matches!(Lexer::new("\"open").tokenize(), Err(LexerError::UnterminatedString(0))));
+        assert!(matches!(Lexer::new(r#""\q""#).tokenize(), Err(LexerError::InvalidEscape(1))));
+        assert!(matches!(Lexer::new(r#""\u{110000}""#).tokenize(), Err(LexerError::InvalidEscape(1))));
+    }
+    
+    #[test]
+    fn test_comparison_operators() {
+        let mut lexer = Lexer::new("a <= -1 != b > c");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Identifier("a".to_string()),
+            Token::LessEqual,
+            Token::Number("-1".to_string()),
+            Token::NotEqual,
+            Token::Identifier("b".to_string()),
+            Token::Greater,
+            Token::Identifier("c".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_interpolated_string() {
+        let mut lexer = Lexer::new(r#"f"side = {x * 2:60.3} {{ok}}""#);
+        let tokens = lexer.tokenize().unwrap();
+        
+        let spec = format::parse_spec("60.3").unwrap();
+        assert_eq!(tokens, vec![
+            Token::FormatStr(vec![
+                TemplatePart::Text("side = ".to_string()),
+                TemplatePart::Field(vec![
+                    Token::Identifier("x".to_string()),
+                    Token::Asterisk,
+                    Token::Number("2".to_string()),
+                    Token::EOF,
+                ], spec),
+                TemplatePart::Text(" {ok}".to_string()),
+            ]),
+            Token::EOF,
+        ]);
+        
+        let error = |input: &str| Lexer::new(input).tokenize();
+        assert!(matches!(error(r#"f"{x""#), Err(LexerError::InvalidFormatString(2, _))));
+        assert!(matches!(error(r#"f"{}""#), Err(LexerError::InvalidFormatString(2, _))));
+        assert!(matches!(error(r#"f"{x:16}""#), Err(LexerError::InvalidFormatString(5, _))));
+        
+        // Fields may hold strings, and their errors point into the source
+        let tokens = Lexer::new(r#"f"{m["a:}"]}""#).tokenize().unwrap();
+        let Token::FormatStr(parts) = &tokens[0] else { panic!("expected a format string") };
+        assert_eq!(parts, &vec![TemplatePart::Field(vec![
+            Token::Identifier("m".to_string()),
+            Token::LBracket,
+            Token::Str("a:}".to_string()),
+            Token::RBracket,
+            Token::EOF,
+        ], format::FormatSpec::default())]);
+        assert!(matches!(error(r#"f"{m["k}""#), Err(LexerError::InvalidFormatString(2, _))));
+        assert!(matches!(error(r#"f"ab {x $ 1}""#), Err(LexerError::UnexpectedCharacter('$', 8))));
+    }
+    
+    #[test]
+    fn test_list_commas() {
+        // A ',' is a place separator only with a digit straight after it
+        let mut lexer = Lexer::new("[1,30, 2] xs[-1:] |x| x");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::LBracket,
+            Token::Number("1,30".to_string()),
+            Token::Comma,
+            Token::Number("2".to_string()),
+            Token::RBracket,
+            Token::Identifier("xs".to_string()),
+            Token::LBracket,
+            Token::Number("-1".to_string()),
+            Token::Colon,
+            Token::RBracket,
+            Token::Pipe,
+            Token::Identifier("x".to_string()),
+            Token::Pipe,
+            Token::Identifier("x".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_separator_adjacency() {
+        let mut lexer = Lexer::new("x = 1;30; y = -1,05 ;2");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Identifier("x".to_string()),
+            Token::Assign,
+            Token::Number("1;30".to_string()),
+            Token::Semicolon,
+            Token::Identifier("y".to_string()),
+            Token::Assign,
+            Token::Number("-1,05".to_string()),
+            Token::Semicolon,
+            Token::Number("2".to_string()),
+            Token::EOF,
+        ]);
+        
+        // Literals written before the rule keep their meaning
+        for literal in ["1;24,51,10", "1,30", "-0;30", "20r1,3,7", "1.5"] {
+            let tokens = Lexer::new(literal).tokenize().unwrap();
+            assert_eq!(tokens, vec![Token::Number(literal.to_string()), Token::EOF]);
+        }
+    }
+    
+    #[test]
+    fn test_map_literal_and_field_access() {
+        let mut lexer = Lexer::new("{width: 1;59}.width");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::LBrace,
+            Token::Identifier("width".to_string()),
+            Token::Colon,
+            Token::Number("1;59".to_string()),
+            Token::RBrace,
+            Token::Dot,
+            Token::Identifier("width".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_match_keywords() {
+        let mut lexer = Lexer::new("match x { 1;30 => -1 }");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Match,
+            Token::Identifier("x".to_string()),
+            Token::LBrace,
+            Token::Number("1;30".to_string()),
+            Token::FatArrow,
+            Token::Number("-1".to_string()),
+            Token::RBrace,
+            Token::EOF,
+        ]);
+        
+        let tokens = Lexer::new("type Shape = Square(side) | Empty").tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Type);
+        assert_eq!(tokens[7], Token::Pipe);
+    }
+    
+    #[test]
+    fn test_ranges_and_pipelines() {
+        let mut lexer = Lexer::new("0..=1 step 0;10 |> map(igi)");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Number("0".to_string()),
+            Token::DotDotEqual,
+            Token::Number("1".to_string()),
+            Token::Step,
+            Token::Number("0;10".to_string()),
+            Token::PipeForward,
+            Token::Identifier("map".to_string()),
+            Token::LParen,
+            Token::Identifier("igi".to_string()),
+            Token::RParen,
+            Token::EOF,
+        ]);
+        
+        let tokens = Lexer::new("1.5..-2").tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Number("1.5".to_string()),
+            Token::DotDot,
+            Token::Number("-2".to_string()),
+            Token::EOF,
+        ]);
+    }
+    
+    #[test]
+    fn test_exception_keywords() {
+        let tokens = Lexer::new("try { throw -1 } catch e { e }").tokenize().unwrap();
+        
+        assert_eq!(&tokens[..5], &[
+            Token::Try,
+            Token::LBrace,
+            Token::Throw,
+            Token::Number("-1".to_string()),
+            Token::RBrace,
+        ]);
+        assert_eq!(tokens[5], Token::Catch);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,21 @@
+//! The Abzu language as a library, for host programs that evaluate
+//! scripts and read the values back
+
+pub mod atf;
+pub mod cuneiform;
+pub mod radix;
+pub mod floating;
+pub mod format;
+pub mod metrology;
+pub mod svg;
+pub mod typeset;
+pub mod lexer;
+pub mod token;
+pub mod value;
+pub mod context;
+pub mod parser;
+pub mod ast;
+pub mod interpreter;
+
+pub use interpreter::{Environment, Interpreter, RuntimeError};
+pub use value::Value;
\ No newline at end of file
@@ -1,26 +1,104 @@
-mod lexer;
-mod token;
-mod value;
-mod parser;
-mod ast;
-mod interpreter;
-
 use std::io::{self, Write};
-use lexer::Lexer;
-use parser::Parser;
-use interpreter::{Interpreter, Environment};
+use std::{env, fs, process};
+use abzu_interpreter::{atf, context, cuneiform, floating, svg, typeset};
+use abzu_interpreter::lexer::Lexer;
+use abzu_interpreter::parser::Parser;
+use abzu_interpreter::{Environment, Interpreter, Value};
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    
+    // abzu atf-check file.atf
+    if let [_, mode, path] = args.as_slice()
+        && mode == "atf-check" {
+        process::exit(run_atf_check(path));
+    }
+    
+    // abzu atf-table file.atf --format latex|mathml|svg
+    if let [_, mode, path, flag, format] = args.as_slice()
+        && mode == "atf-table" && flag == "--format" {
+        process::exit(run_atf_table(path, format));
+    }
+    
+    // abzu doc script.abzu
+    if let [_, mode, path] = args.as_slice()
+        && mode == "doc" {
+        process::exit(run_doc(path));
+    }
+    
+    // abzu svg-table reciprocal, abzu svg-table 7,30
+    if let [_, mode, table] = args.as_slice()
+        && mode == "svg-table" {
+        process::exit(run_svg_table(table));
+    }
+    
+    // abzu --format latex
+    let notation = match args.as_slice() {
+        [_, flag, format] if flag == "--format" => match Notation::from_name(format) {
+            Some(notation) => notation,
+            None => {
+                println!("Unknown format: {}", format);
+                process::exit(2);
+            }
+        },
+        _ => Notation::Decimal,
+    };
+    
     println!("ENU Interpreter");
     println!("Sexagecimal Programming Language with Cuneiform bindings");
     println!("Type 'exit' to quit\n");
     
-    start_repl();
+    start_repl(notation);
+}
+
+/// Notation the REPL prints numbers in
+enum Notation {
+    Decimal,
+    Cuneiform,
+    Transliteration,
+    Latex,
+    MathML,
+}
+
+impl Notation {
+    /// Names accepted by :output and --format
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "decimal" => Some(Notation::Decimal),
+            "cuneiform" => Some(Notation::Cuneiform),
+            "atf" => Some(Notation::Transliteration),
+            "latex" => Some(Notation::Latex),
+            "mathml" => Some(Notation::MathML),
+            _ => None,
+        }
+    }
+}
+
+/// How the REPL prints results
+struct Output {
+    notation: Notation,
+    /// Written between places in cuneiform output
+    separator: String,
 }
 
-fn start_repl() {
+impl Output {
+    /// Writes a value in the chosen notation; floats are rounded to
+    /// `places` sexagesimal places
+    fn format(&self, value: &Value, places: usize) -> String {
+        match self.notation {
+            Notation::Decimal => value.to_string(),
+            Notation::Cuneiform => value.to_cuneiform(&self.separator, places),
+            Notation::Transliteration => value.to_transliteration(places),
+            Notation::Latex => typeset::latex(value, places),
+            Notation::MathML => typeset::mathml(value, places),
+        }
+    }
+}
+
+fn start_repl(notation: Notation) {
     let mut environment = Environment::new();
     let mut interpreter = Interpreter::new();
+    let mut output = Output { notation, separator: cuneiform::PLACE_SEPARATOR.to_string() };
     
     loop {
         print!("𒀜> ");
@@ -39,6 +117,12 @@ fn start_repl() {
             continue;
         }
         
+        // REPL commands start with ':'
+        if let Some(command) = input.strip_prefix(':') {
+            handle_command(command, &mut interpreter, &mut output);
+            continue;
+        }
+        
         // Create lexer and tokenize input
         let mut lexer = Lexer::new(input);
         let tokens = match lexer.tokenize() {
@@ -61,7 +145,7 @@ fn start_repl() {
                 match interpreter.eval_program(&program, &mut environment) {
                     Ok(result) => {
                         if let Some(value) = result {
-                            println!("Result: {}", value);
+                            println!("Result: {}", output.format(&value, interpreter.context().places));
                         }
                     }
                     Err(e) => {
@@ -76,4 +160,126 @@ fn start_repl() {
     }
     
     println!("𒆠𒂗𒈾 (Goodbye!)");
+}
+
+/// Checks the relations declared in an ATF file; the exit code is 1 if
+/// any row disagrees and 2 if the file could not be checked
+fn run_atf_check(path: &str) -> i32 {
+    let result = fs::read_to_string(path)
+        .map_err(atf::AtfError::from)
+        .and_then(|source| atf::check(&source));
+    
+    match result {
+        Ok(discrepancies) if discrepancies.is_empty() => {
+            println!("{}: all declared relations hold", path);
+            0
+        }
+        Ok(discrepancies) => {
+            for discrepancy in &discrepancies {
+                println!("{}: {}", path, discrepancy);
+            }
+            1
+        }
+        Err(e) => {
+            println!("ATF Error: {}", e);
+            2
+        }
+    }
+}
+
+/// Exports the numbers of an ATF file as a table, one row per line
+/// headed by its label
+fn run_atf_table(path: &str, format: &str) -> i32 {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            println!("ATF Error: {}", atf::AtfError::from(e));
+            return 2;
+        }
+    };
+    let rows: Vec<Vec<Value>> = atf::numeric_lines(&source)
+        .into_iter()
+        .map(|row| std::iter::once(Value::Str(row.label)).chain(row.values).collect())
+        .collect();
+    
+    let places = context::NumericContext::new().places;
+    match format {
+        "latex" => println!("{}", typeset::latex_table(&rows, places)),
+        "mathml" => println!("{}", typeset::mathml_table(&rows, places)),
+        "svg" => println!("{}", svg::render_table(&rows, places)),
+        _ => {
+            println!("Tables export to latex, mathml or svg, not '{}'", format);
+            return 2;
+        }
+    }
+    0
+}
+
+/// Lists the comments of a script with the lines they start on, so
+/// annotations can be read without the code around them
+fn run_doc(path: &str) -> i32 {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            println!("Cannot read {}: {}", path, e);
+            return 2;
+        }
+    };
+    let mut lexer = Lexer::new(&source);
+    if let Err(e) = lexer.tokenize() {
+        println!("Lexer Error: {}", e);
+        return 2;
+    }
+    
+    for comment in lexer.comments() {
+        let line = source.chars().take(comment.span.start).filter(|ch| *ch == '\n').count() + 1;
+        println!("{}:{}: {}", path, line, comment.text);
+    }
+    0
+}
+
+/// Draws a standard school table onto one SVG tablet: the reciprocal
+/// table, or the multiplication table of a head number such as 7,30
+fn run_svg_table(table: &str) -> i32 {
+    let rows = if table == "reciprocal" {
+        svg::reciprocal_table()
+    } else {
+        match floating::parse_floating(table) {
+            Ok(head) => svg::multiplication_table(&head),
+            Err(e) => {
+                println!("Expected 'reciprocal' or a head number: {}", e);
+                return 2;
+            }
+        }
+    };
+    println!("{}", svg::render_table(&rows, context::NumericContext::new().places));
+    0
+}
+
+fn handle_command(command: &str, interpreter: &mut Interpreter, output: &mut Output) {
+    let parts: Vec<&str> = command.split_whitespace().collect();
+    
+    match parts.as_slice() {
+        ["context"] => println!("{}", interpreter.context()),
+        // :output decimal|cuneiform|atf|latex|mathml
+        ["output", name] => match Notation::from_name(name) {
+            Some(notation) => output.notation = notation,
+            None => println!("Unknown output notation: {}", name),
+        },
+        // :separator space, :separator ,
+        ["separator", "space"] => output.separator = " ".to_string(),
+        ["separator", separator] => output.separator = separator.to_string(),
+        // :places 6, :rounding truncate, :promotion sexagesimal, :overflow promote
+        [name, value] => {
+            let mut context = interpreter.context();
+            match context.apply(name, value) {
+                Ok(()) => {
+                    interpreter.set_context(context);
+                    println!("{}", context);
+                }
+                Err(e) => println!("Context Error: {}", e),
+            }
+        }
+        _ => println!("Unknown command: :{}", command),
+    }
 }
\ No newline at end of file
@@ -0,0 +1,235 @@
+use crate::value::{Rounding, SexagesimalNum, Value};
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Physical dimension of an Old Babylonian measure
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dimension {
+    Length,
+    /// Length squared: ninda × ninda = sar
+    Area,
+    Capacity,
+    Weight,
+    Angle,
+    Time,
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Dimension::Length => write!(f, "length"),
+            Dimension::Area => write!(f, "area"),
+            Dimension::Capacity => write!(f, "capacity"),
+            Dimension::Weight => write!(f, "weight"),
+            Dimension::Angle => write!(f, "angle"),
+            Dimension::Time => write!(f, "time"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    pub name: &'static str,
+    /// Other accepted spellings, matched after `normalize`
+    aliases: &'static [&'static str],
+    pub dimension: Dimension,
+    /// Size in the smallest unit of the dimension: šu-si for length,
+    /// square šu-si for area, sila for capacity, še for weight, arcsec
+    /// for angles and seconds for time
+    pub size: Size,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Size {
+    Exact(u64),
+    /// Units such as the radian that are no whole number of base units
+    Approximate(f64),
+}
+
+impl Unit {
+    /// The size as a number, to scale amounts by
+    pub fn size_value(&self) -> Value {
+        match self.size {
+            Size::Exact(size) => Value::from_bigint(size.into()),
+            Size::Approximate(size) => Value::Float(size),
+        }
+    }
+}
+
+const fn unit(
+    name: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    size: u64,
+) -> Unit {
+    Unit { name, aliases, dimension, size: Size::Exact(size) }
+}
+
+/// The standard Old Babylonian systems: 30 šu-si = 1 kuš, 12 kuš =
+/// 1 ninda, 60 ninda = 1 uš; 1 sar = 1 ninda², 100 sar = 1 iku,
+/// 18 iku = 1 bur; 10 sila = 1 ban, 6 ban = 1 bariga, 5 bariga = 1 gur;
+/// 180 še = 1 gin, 60 gin = 1 ma-na, 60 ma-na = 1 gun. Angles and time
+/// keep their sexagesimal subdivisions.
+static UNITS: &[Unit] = &[
+    unit("šu-si", &["finger"], Dimension::Length, 1),
+    unit("kuš", &["cubit"], Dimension::Length, 30),
+    unit("ninda", &["rod"], Dimension::Length, 360),
+    unit("uš", &[], Dimension::Length, 21_600),
+    unit("sar", &[], Dimension::Area, 129_600),
+    unit("iku", &[], Dimension::Area, 12_960_000),
+    unit("bur", &[], Dimension::Area, 233_280_000),
+    unit("sila", &["qa"], Dimension::Capacity, 1),
+    unit("ban", &["ban2"], Dimension::Capacity, 10),
+    unit("bariga", &[], Dimension::Capacity, 60),
+    unit("gur", &[], Dimension::Capacity, 300),
+    unit("še", &["grain"], Dimension::Weight, 1),
+    unit("gin", &["gin2", "shekel"], Dimension::Weight, 180),
+    unit("ma-na", &["mina"], Dimension::Weight, 10_800),
+    unit("gun", &["gun2", "talent"], Dimension::Weight, 648_000),
+    unit("arcsec", &[], Dimension::Angle, 1),
+    unit("arcmin", &[], Dimension::Angle, 60),
+    unit("deg", &["degree", "°"], Dimension::Angle, 3600),
+    Unit { name: "rad", aliases: &["radian"], dimension: Dimension::Angle, size: Size::Approximate(648_000.0 / PI) },
+    unit("s", &["sec", "second"], Dimension::Time, 1),
+    unit("min", &["minute"], Dimension::Time, 60),
+    unit("h", &["hour"], Dimension::Time, 3600),
+    unit("day", &[], Dimension::Time, 86_400),
+];
+
+/// Spelling-insensitive form of a unit name, so that ŠU.SI, šu-si,
+/// shusi and susi all match
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .replace(['-', '.', '₂'], "")
+        .replace("sz", "s")
+        .replace("sh", "s")
+        .replace('š', "s")
+}
+
+/// Looks up a unit by name or alias
+pub fn find_unit(name: &str) -> Option<&'static Unit> {
+    let name = normalize(name);
+    UNITS.iter().find(|unit| {
+        normalize(unit.name) == name || unit.aliases.iter().any(|alias| normalize(alias) == name)
+    })
+}
+
+/// A unit written with hyphens or dots, such as ma-na or ŠU.SI. Only
+/// spellings that are hyphenated themselves match, segment by segment, so
+/// that u-s stays a subtraction and s.h a field access.
+pub fn find_compound_unit(name: &str) -> Option<&'static Unit> {
+    let segments = |name: &str| name.split(['-', '.']).map(normalize).collect::<Vec<_>>();
+    if name.split(['-', '.']).any(|segment| segment.chars().count() < 2) {
+        return None;
+    }
+    let written = segments(name);
+    UNITS.iter().find(|unit| {
+        std::iter::once(unit.name)
+            .chain(unit.aliases.iter().copied())
+            .any(|spelling| spelling.contains(['-', '.']) && segments(spelling) == written)
+    })
+}
+
+/// The unit that products of two lengths are expressed in
+pub fn area_unit() -> &'static Unit {
+    find_unit("sar").expect("sar is a built-in unit")
+}
+
+/// A number with a unit, such as 3 ninda. The amount is counted in
+/// `unit`; conversions go through the unit sizes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    pub amount: Box<Value>,
+    pub unit: &'static Unit,
+}
+
+impl Quantity {
+    pub fn new(amount: Value, unit: &'static Unit) -> Self {
+        Quantity { amount: Box::new(amount), unit }
+    }
+}
+
+impl fmt::Display for Quantity {
+    /// Degrees display as 12°30'15" and hours as 1:02:03.5
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sexagesimal = match self.amount.as_ref() {
+            Value::Integer(i) => Some(SexagesimalNum::from_integer(*i)),
+            Value::Sexagesimal(sex) => Some(sex.clone()),
+            Value::Float(n) => SexagesimalNum::from_f64_rounded(*n, 4, Rounding::HalfEven),
+            _ => None,
+        };
+        
+        match (self.unit.name, sexagesimal) {
+            ("deg" | "h", Some(sex)) => {
+                let approximate = if self.amount.is_exact() { "" } else { "≈" };
+                let sign = if sex.negative { "-" } else { "" };
+                let place = |index: usize| sex.fractional_places.get(index).copied().unwrap_or(0);
+                let seconds = format!("{:02}{}", place(1), decimal_seconds(&sex.fractional_places));
+                
+                if self.unit.name == "h" {
+                    return write!(f, "{}{}{}:{:02}:{}", approximate, sign, sex.integer_part, place(0), seconds);
+                }
+                write!(f, "{}{}{}°", approximate, sign, sex.integer_part)?;
+                match sex.places() {
+                    0 => Ok(()),
+                    1 => write!(f, "{:02}'", place(0)),
+                    _ => write!(f, "{:02}'{}\"", place(0), seconds),
+                }
+            }
+            _ => write!(f, "{} {}", self.amount, self.unit.name),
+        }
+    }
+}
+
+/// Places after the seconds as a decimal fraction, e.g. ".5" for [30];
+/// decimals that do not terminate are cut to three digits
+fn decimal_seconds(places: &[u8]) -> String {
+    let fraction = places.iter()
+        .skip(2)
+        .rev()
+        .fold(0.0, |fraction, place| (fraction + *place as f64) / 60.0);
+    if fraction == 0.0 {
+        return String::new();
+    }
+    // Fractions that round to 0.000 leave nothing after the point
+    let digits = format!("{:.3}", fraction.min(0.999));
+    let digits = digits.trim_start_matches('0').trim_end_matches('0');
+    if digits == "." { String::new() } else { digits.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_unit_spellings() {
+        for name in ["šu-si", "ŠU.SI", "shusi", "susi", "finger"] {
+            assert_eq!(find_unit(name).map(|unit| unit.name), Some("šu-si"));
+        }
+        assert_eq!(find_unit("ma-na").map(|unit| unit.name), Some("ma-na"));
+        assert_eq!(find_unit("mina").map(|unit| unit.name), Some("ma-na"));
+        assert_eq!(find_unit("UŠ").map(|unit| unit.name), Some("uš"));
+        assert_eq!(find_unit("kusz").map(|unit| unit.name), Some("kuš"));
+        assert!(find_unit("furlong").is_none());
+    }
+
+    #[test]
+    fn test_unit_sizes() {
+        let size = |name: &str| match find_unit(name).unwrap().size {
+            Size::Exact(size) => size,
+            Size::Approximate(_) => panic!("{} has no exact size", name),
+        };
+        
+        assert_eq!(size("ninda") * size("ninda"), size("sar"));
+        assert_eq!(size("bur"), 18 * size("iku"));
+        assert_eq!(size("gur"), 300 * size("sila"));
+        assert_eq!(size("gun"), 60 * size("ma-na"));
+    }
+
+    #[test]
+    fn test_decimal_seconds() {
+        assert_eq!(decimal_seconds(&[30, 15, 30]), ".5");
+        assert_eq!(decimal_seconds(&[30, 15, 0, 1]), "");
+        assert_eq!(decimal_seconds(&[30, 15]), "");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,367 @@
+use crate::value::{NumberError, Rounding, SexagesimalNum};
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Fractional places to expand before giving up on finding a period
+const MAX_PLACES: usize = 120;
+
+/// A number written in positional notation with an arbitrary radix per
+/// place, e.g. base 20, base 12, or the Maya long count (20, 18, 20, ...).
+/// The value is an exact fraction, so arithmetic and conversion between
+/// radices never round; the radix only affects how the places are
+/// written. `SexagesimalNum` stays the base-60 type behind cuneiform,
+/// ATF and the numeric context, and converts to and from this one exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadixNum {
+    /// Radix of each place, least significant first. The last radix
+    /// repeats for all higher places, so [20] is plain base 20 and
+    /// [20, 18, 20] is the Maya long count. Fractional places use the
+    /// radix of the units place.
+    pub radices: Vec<u32>,
+    /// The value in lowest terms, with a positive denominator
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+impl RadixNum {
+    /// Writes `value` in the given radices (least significant first)
+    pub fn from_bigint(value: &BigInt, radices: Vec<u32>) -> Self {
+        RadixNum { radices: normalize_radices(radices), numerator: value.clone(), denominator: BigInt::one() }
+    }
+
+    /// Writes numerator / denominator in the given radices; None if the
+    /// denominator is zero
+    pub fn from_fraction(numerator: &BigInt, denominator: &BigInt, radices: Vec<u32>) -> Option<Self> {
+        if denominator.is_zero() {
+            return None;
+        }
+        let divisor = numerator.gcd(denominator);
+        let divisor = if denominator.is_negative() { -divisor } else { divisor };
+        Some(RadixNum {
+            radices: normalize_radices(radices),
+            numerator: numerator / &divisor,
+            denominator: denominator / &divisor,
+        })
+    }
+
+    /// The exact value of a sexagesimal number, written in `radices`
+    pub fn from_sexagesimal(sex: &SexagesimalNum, radices: Vec<u32>) -> Self {
+        let places = sex.places();
+        let denominator = BigInt::from(60).pow(places as u32);
+        RadixNum::from_fraction(&sex.to_scaled(places), &denominator, radices)
+            .expect("powers of 60 are not zero")
+    }
+
+    /// The same value written in other radices
+    pub fn with_radices(&self, radices: Vec<u32>) -> Self {
+        RadixNum { radices: normalize_radices(radices), ..self.clone() }
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    /// The value if it is a whole number
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        self.denominator.is_one().then(|| self.numerator.clone())
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match (self.numerator.to_f64(), self.denominator.to_f64()) {
+            (Some(numerator), Some(denominator)) => numerator / denominator,
+            _ => f64::NAN,
+        }
+    }
+
+    /// Rounds to `places` sexagesimal places; None if the integer part
+    /// does not fit in a u64
+    pub fn to_sexagesimal(&self, places: usize, rounding: Rounding) -> Option<SexagesimalNum> {
+        let numerator = &self.numerator * BigInt::from(60).pow(places as u32);
+        let exact = (&numerator % &self.denominator).is_zero();
+        SexagesimalNum::from_scaled(&rounding.divide(&numerator, &self.denominator), places)
+            .map(|sex| sex.with_exactness(exact))
+    }
+
+    pub fn negated(&self) -> Self {
+        RadixNum { numerator: -&self.numerator, ..self.clone() }
+    }
+
+    /// Sum, difference and product keep the radices of `self`
+    pub fn add(&self, other: &Self) -> Self {
+        self.fraction(
+            &self.numerator * &other.denominator + &other.numerator * &self.denominator,
+            &self.denominator * &other.denominator,
+        )
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.negated())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        self.fraction(&self.numerator * &other.numerator, &self.denominator * &other.denominator)
+    }
+
+    /// None when dividing by zero
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        RadixNum::from_fraction(
+            &(&self.numerator * &other.denominator),
+            &(&self.denominator * &other.numerator),
+            self.radices.clone(),
+        )
+    }
+
+    /// Floor division: a whole quotient and a remainder with the sign of
+    /// the divisor. None when dividing by zero.
+    pub fn div_rem_floor(&self, other: &Self) -> Option<(Self, Self)> {
+        let quotient = self.checked_div(other)?;
+        let whole = quotient.numerator.div_floor(&quotient.denominator);
+        let whole = RadixNum::from_bigint(&whole, self.radices.clone());
+        let remainder = self.sub(&other.mul(&whole));
+        Some((whole, remainder))
+    }
+
+    /// Non-negative integer power
+    pub fn pow(&self, exp: u32) -> Self {
+        self.fraction(self.numerator.pow(exp), self.denominator.pow(exp))
+    }
+
+    fn fraction(&self, numerator: BigInt, denominator: BigInt) -> Self {
+        RadixNum::from_fraction(&numerator, &denominator, self.radices.clone())
+            .expect("denominators are not zero")
+    }
+
+    /// Radix of the place `index` positions above the units place
+    fn radix_of_place(&self, index: usize) -> u32 {
+        self.radices[index.min(self.radices.len() - 1)]
+    }
+
+    /// Integer places (most significant first), fractional places, the
+    /// index where a repeating period starts, and whether the expansion
+    /// was cut short
+    fn places(&self) -> (Vec<u32>, Vec<u32>, Option<usize>, bool) {
+        let (mut rest, mut remainder) = self.numerator.magnitude().div_rem(self.denominator.magnitude());
+        
+        let mut integer = Vec::new();
+        for index in 0.. {
+            let (quotient, place) = rest.div_rem(&BigUint::from(self.radix_of_place(index)));
+            integer.push(place.to_u32().unwrap_or(0));
+            rest = quotient;
+            if rest.is_zero() {
+                break;
+            }
+        }
+        integer.reverse();
+        
+        // Long division; the period starts where a remainder comes back
+        let radix = self.radices[0];
+        let mut fractional = Vec::new();
+        let mut seen = HashMap::new();
+        let mut repeat_from = None;
+        while !remainder.is_zero() && fractional.len() < MAX_PLACES {
+            if let Some(index) = seen.insert(remainder.clone(), fractional.len()) {
+                repeat_from = Some(index);
+                break;
+            }
+            let (place, rest) = (remainder * radix).div_rem(self.denominator.magnitude());
+            fractional.push(place.to_u32().unwrap_or(0));
+            remainder = rest;
+        }
+        
+        let cut_short = repeat_from.is_none() && !remainder.is_zero();
+        (integer, fractional, repeat_from, cut_short)
+    }
+}
+
+/// [20, 20] and [20] are the same system
+fn normalize_radices(mut radices: Vec<u32>) -> Vec<u32> {
+    while radices.len() > 1 && radices[radices.len() - 1] == radices[radices.len() - 2] {
+        radices.pop();
+    }
+    radices
+}
+
+/// Parses a radix literal: `20r1,3,7` is 1×400 + 3×20 + 7. A mixed radix
+/// lists the place radices from the most significant down, joined by
+/// '_', and the first one repeats for higher places: `20_18_20r9,12,11,16,0`.
+/// Fractional places follow a ';' in the radix of the units place, with a
+/// repeating period in parentheses: `12r1;6` is 1.5 and `20r0;(6,13)` is 1/3.
+pub fn parse_radix(s: &str) -> Result<RadixNum, NumberError> {
+    let invalid = |reason: &str| NumberError::InvalidFormat(format!("{} ({})", s, reason));
+    let (negative, body) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (spec, digits) = body.split_once('r').ok_or_else(|| invalid("missing 'r'"))?;
+    
+    let mut radices = spec.split('_')
+        .map(|radix| radix.parse::<u32>().ok().filter(|radix| *radix >= 2))
+        .collect::<Option<Vec<u32>>>()
+        .ok_or_else(|| invalid("radices must be integers of at least 2"))?;
+    radices.reverse();
+    let number = RadixNum::from_bigint(&BigInt::zero(), radices.clone());
+    
+    let (integer, fraction) = match digits.split_once(';') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits, None),
+    };
+    let integer = parse_places(integer).ok_or_else(|| invalid("places must be integers"))?;
+    let count = integer.len();
+    let mut value = BigInt::zero();
+    for (index, place) in integer.iter().enumerate() {
+        let radix = number.radix_of_place(count - 1 - index);
+        if *place >= radix {
+            return Err(invalid("a place is not below its radix"));
+        }
+        value = value * radix + *place;
+    }
+
+    // 0;d₁…dₖ(r₁…rₘ) = (D·(bᵐ - 1) + R) / (bᵏ·(bᵐ - 1)), with a period of
+    // length 0 counting as bᵐ - 1 = 1
+    let mut numerator = value;
+    let mut denominator = BigInt::one();
+    if let Some(fraction) = fraction {
+        let (fixed, period) = match fraction.split_once('(') {
+            Some((fixed, period)) => {
+                let period = period.strip_suffix(')').ok_or_else(|| invalid("unclosed period"))?;
+                let fixed = fixed.strip_suffix(',').unwrap_or(fixed);
+                (fixed, Some(period))
+            }
+            None => (fraction, None),
+        };
+        let fixed = if fixed.is_empty() { Vec::new() } else {
+            parse_places(fixed).ok_or_else(|| invalid("places must be integers"))?
+        };
+        let period = match period {
+            Some(period) => parse_places(period).ok_or_else(|| invalid("places must be integers"))?,
+            None => Vec::new(),
+        };
+        if fixed.is_empty() && period.is_empty() {
+            return Err(invalid("missing fractional places"));
+        }
+        
+        let radix = BigInt::from(radices[0]);
+        if fixed.iter().chain(&period).any(|place| BigInt::from(*place) >= radix) {
+            return Err(invalid("a place is not below its radix"));
+        }
+        let read = |places: &[u32]| places.iter().fold(BigInt::zero(), |total, place| total * &radix + *place);
+        let cycle = if period.is_empty() { BigInt::one() } else { radix.pow(period.len() as u32) - 1 };
+        let scale = radix.pow(fixed.len() as u32);
+        
+        numerator = (numerator * &scale + read(&fixed)) * &cycle + read(&period);
+        denominator = scale * cycle;
+    }
+
+    let numerator = if negative { -numerator } else { numerator };
+    RadixNum::from_fraction(&numerator, &denominator, radices).ok_or_else(|| invalid("zero denominator"))
+}
+
+fn parse_places(places: &str) -> Option<Vec<u32>> {
+    places.split(',').map(|place| place.parse::<u32>().ok()).collect()
+}
+
+impl fmt::Display for RadixNum {
+    /// Literal syntax, e.g. 20r1,3,7, 20_18_20r9,12,11,16,0 or 20r0;(6,13);
+    /// expansions too long to find a period end in ≈
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let spec: Vec<String> = self.radices.iter().rev().map(|radix| radix.to_string()).collect();
+        let join = |places: &[u32]| places.iter().map(|place| place.to_string()).collect::<Vec<_>>().join(",");
+        let (integer, fractional, repeat_from, cut_short) = self.places();
+        let sign = if self.numerator.is_negative() { "-" } else { "" };
+        let approximate = if cut_short { "≈" } else { "" };
+        
+        write!(f, "{}{}{}r{}", approximate, sign, spec.join("_"), join(&integer))?;
+        match repeat_from {
+            _ if fractional.is_empty() => Ok(()),
+            None => write!(f, ";{}", join(&fractional)),
+            Some(0) => write!(f, ";({})", join(&fractional)),
+            Some(index) => {
+                let (fixed, period) = fractional.split_at(index);
+                write!(f, ";{},({})", join(fixed), join(period))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uniform_radix() {
+        let number = parse_radix("20r1,3,7").unwrap();
+        
+        assert_eq!(number.to_bigint(), Some(BigInt::from(467)));
+        assert_eq!(number.to_string(), "20r1,3,7");
+        assert_eq!(parse_radix("-12r0,1,11").unwrap().to_string(), "-12r1,11");
+    }
+
+    #[test]
+    fn test_maya_long_count() {
+        // 9.12.11.16.0: 9×144000 + 12×7200 + 11×360 + 16×20 + 0
+        let number = parse_radix("20_18_20r9,12,11,16,0").unwrap();
+        
+        assert_eq!(number.to_bigint(), Some(BigInt::from(1386680)));
+        assert_eq!(number.radices, vec![20, 18, 20]);
+        assert_eq!(number.to_string(), "20_18_20r9,12,11,16,0");
+        assert!(parse_radix("20_18_20r1,18,0").is_err());
+    }
+
+    #[test]
+    fn test_conversion_is_exact() {
+        let number = parse_radix("24_60_60r1,2,3").unwrap();
+        let converted = number.with_radices(vec![20]);
+        
+        assert_eq!(converted.to_string(), "20r9,6,3");
+        assert_eq!(converted.with_radices(number.radices.clone()), number);
+        
+        // A third ends in base 12 but repeats in base 20
+        let third = parse_radix("12r0;4").unwrap();
+        assert_eq!(third.with_radices(vec![20]).to_string(), "20r0;(6,13)");
+        assert_eq!(parse_radix("20r0;(6,13)").unwrap().with_radices(vec![12]), third);
+        assert_eq!(parse_radix("10r0;1,(6)").unwrap().with_radices(vec![60]).to_string(), "60r0;10");
+    }
+
+    #[test]
+    fn test_fraction_arithmetic() {
+        let half = parse_radix("12r0;6").unwrap();
+        let third = parse_radix("12r0;4").unwrap();
+        
+        assert_eq!(half.add(&third).to_string(), "12r0;10");
+        assert_eq!(half.sub(&third).to_string(), "12r0;2");
+        assert_eq!(half.mul(&third).to_string(), "12r0;2");
+        assert_eq!(half.checked_div(&third).unwrap().to_string(), "12r1;6");
+        assert_eq!(third.pow(2).to_string(), "12r0;1,4");
+        assert!(half.checked_div(&RadixNum::from_bigint(&BigInt::zero(), vec![12])).is_none());
+        
+        let (whole, remainder) = parse_radix("12r7;6").unwrap().div_rem_floor(&third).unwrap();
+        assert_eq!((whole.to_string(), remainder.to_string()), ("12r1,10".to_string(), "12r0;2".to_string()));
+    }
+
+    #[test]
+    fn test_sexagesimal_round_trip() {
+        let sex = SexagesimalNum::from_parts(false, 1, vec![24, 51, 10]);
+        let number = RadixNum::from_sexagesimal(&sex, vec![60]);
+        
+        assert_eq!(number.to_string(), "60r1;24,51,10");
+        assert_eq!(number.to_sexagesimal(3, Rounding::HalfEven), Some(sex));
+        assert!(!parse_radix("7r0;1").unwrap().to_sexagesimal(3, Rounding::HalfEven).unwrap().exact);
+    }
+
+    #[test]
+    fn test_invalid_radix_literals() {
+        assert!(parse_radix("1r0").is_err());
+        assert!(parse_radix("20r").is_err());
+        assert!(parse_radix("20r20").is_err());
+        assert!(parse_radix("12r0;12").is_err());
+        assert!(parse_radix("12r0;(1").is_err());
+        assert!(parse_radix("12r0;").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,271 @@
+use crate::cuneiform;
+use crate::floating::FloatingNum;
+use crate::typeset::escape_xml;
+use crate::value::Value;
+use num_bigint::BigInt;
+use num_traits::Zero;
+
+/// Height of a line of wedges, in SVG user units
+const LINE_HEIGHT: f64 = 30.0;
+const WEDGE_WIDTH: f64 = 10.0;
+const WINKELHAKEN_WIDTH: f64 = 12.0;
+/// Space between wedges in a stack
+const WEDGE_GAP: f64 = 2.0;
+/// Space between the tens and units of a place
+const GROUP_GAP: f64 = 4.0;
+const PLACE_GAP: f64 = 14.0;
+const COLUMN_GAP: f64 = 36.0;
+const ROW_GAP: f64 = 14.0;
+const MARGIN: f64 = 24.0;
+const FONT_SIZE: f64 = 16.0;
+
+const CLAY: &str = "#d9c29c";
+const CLAY_EDGE: &str = "#a88b5e";
+const IMPRESSION: &str = "#4a3a28";
+
+/// Rows of the standard unit stacks, top first: 4 diš is written 3 over
+/// 1, 7 diš 4 over 3 and 9 diš in three rows of 3
+const UNIT_STACKS: [&[usize]; 9] = [&[1], &[2], &[3], &[3, 1], &[3, 2], &[3, 3], &[4, 3], &[4, 4], &[3, 3, 3]];
+/// Rows of the Winkelhaken stacks for 10 to 50
+const TEN_STACKS: [&[usize]; 5] = [&[1], &[2], &[3], &[2, 2], &[3, 2]];
+
+#[derive(Debug, Clone, Copy)]
+enum Wedge {
+    /// Upright wedge (diš), head at the top
+    Vertical,
+    /// Corner wedge (u), opening to the right
+    Winkelhaken,
+}
+
+impl Wedge {
+    fn width(self) -> f64 {
+        match self {
+            Wedge::Vertical => WEDGE_WIDTH,
+            Wedge::Winkelhaken => WINKELHAKEN_WIDTH,
+        }
+    }
+    
+    /// Outline of the wedge impressed with its top-left corner at (x, y)
+    fn path(self, x: f64, y: f64, height: f64) -> String {
+        let points = match self {
+            Wedge::Vertical => {
+                let head = (height * 0.4).min(8.0);
+                vec![
+                    (x, y),
+                    (x + WEDGE_WIDTH, y),
+                    (x + WEDGE_WIDTH * 0.58, y + head),
+                    (x + WEDGE_WIDTH * 0.55, y + height),
+                    (x + WEDGE_WIDTH * 0.45, y + height),
+                    (x + WEDGE_WIDTH * 0.42, y + head),
+                ]
+            }
+            Wedge::Winkelhaken => vec![
+                (x + WINKELHAKEN_WIDTH, y),
+                (x, y + height / 2.0),
+                (x + WINKELHAKEN_WIDTH, y + height),
+                (x + WINKELHAKEN_WIDTH * 0.6, y + height / 2.0),
+            ],
+        };
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+        format!("<path d=\"M{} Z\"/>", points.join(" L"))
+    }
+}
+
+/// Draws a stack of wedges, rows shrinking to share the line height;
+/// returns the width
+fn draw_stack(elements: &mut Vec<String>, wedge: Wedge, rows: &[usize], x: f64, y: f64) -> f64 {
+    let count = rows.len() as f64;
+    let height = (LINE_HEIGHT - WEDGE_GAP * (count - 1.0)) / count;
+    for (row, wedges) in rows.iter().enumerate() {
+        let top = y + row as f64 * (height + WEDGE_GAP);
+        for column in 0..*wedges {
+            elements.push(wedge.path(x + column as f64 * (wedge.width() + WEDGE_GAP), top, height));
+        }
+    }
+    let widest = rows.iter().max().copied().unwrap_or(0) as f64;
+    widest * (wedge.width() + WEDGE_GAP) - WEDGE_GAP
+}
+
+/// Draws one base-60 place, tens before units. An empty place is left
+/// blank, as on Old Babylonian tablets.
+fn draw_place(elements: &mut Vec<String>, place: u8, x: f64, y: f64) -> f64 {
+    let (tens, units) = (place as usize / 10, place as usize % 10);
+    if place == 0 {
+        return WEDGE_WIDTH;
+    }
+    
+    let mut width = 0.0;
+    if tens > 0 {
+        width = draw_stack(elements, Wedge::Winkelhaken, TEN_STACKS[tens - 1], x, y);
+    }
+    if units > 0 {
+        if width > 0.0 {
+            width += GROUP_GAP;
+        }
+        width += draw_stack(elements, Wedge::Vertical, UNIT_STACKS[units - 1], x + width, y);
+    }
+    width
+}
+
+fn draw_number(elements: &mut Vec<String>, places: &[u8], x: f64, y: f64) -> f64 {
+    let mut width = 0.0;
+    for (index, place) in places.iter().enumerate() {
+        if index > 0 {
+            width += PLACE_GAP;
+        }
+        width += draw_place(elements, *place, x + width, y);
+    }
+    width
+}
+
+/// Places as written on a tablet: nothing marks where the fraction
+/// starts, so 1;30 and 1,30 look alike. None for negative numbers and
+/// values that are not plain numbers.
+fn tablet_places(value: &Value, places: usize) -> Option<Vec<u8>> {
+    let (negative, integer, fractional) = value.to_places(places)?;
+    if negative {
+        return None;
+    }
+    let mut written = if integer.is_zero() && !fractional.is_empty() {
+        Vec::new()
+    } else {
+        cuneiform::integer_places(&integer)
+    };
+    written.extend(fractional);
+    Some(written)
+}
+
+/// Draws a table cell at the origin: wedges for numbers, text otherwise
+fn draw_cell(value: &Value, places: usize) -> (Vec<String>, f64) {
+    let mut elements = Vec::new();
+    if let Some(written) = tablet_places(value, places) {
+        let width = draw_number(&mut elements, &written, 0.0, 0.0);
+        return (elements, width);
+    }
+    let text = value.to_string();
+    elements.push(format!(
+        "<text x=\"0\" y=\"{:.1}\" font-family=\"serif\" font-size=\"{}\">{}</text>",
+        (LINE_HEIGHT + FONT_SIZE) / 2.0, FONT_SIZE, escape_xml(&text)
+    ));
+    (elements, text.chars().count() as f64 * FONT_SIZE * 0.6)
+}
+
+fn tablet(width: f64, height: f64, elements: &[String]) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\">\n\
+         <rect x=\"1\" y=\"1\" width=\"{:.0}\" height=\"{:.0}\" rx=\"18\" fill=\"{}\" stroke=\"{}\" stroke-width=\"2\"/>\n\
+         <g fill=\"{}\">\n{}\n</g>\n</svg>",
+        width - 2.0, height - 2.0, CLAY, CLAY_EDGE, IMPRESSION, elements.join("\n"),
+        w = width, h = height,
+    )
+}
+
+/// An SVG tablet with a number impressed in wedges, place by place.
+/// Floats are rounded to `places` sexagesimal places; None for negative
+/// numbers and other values.
+pub fn render(value: &Value, places: usize) -> Option<String> {
+    let written = tablet_places(value, places)?;
+    let mut elements = Vec::new();
+    let width = draw_number(&mut elements, &written, MARGIN, MARGIN);
+    Some(tablet(width + 2.0 * MARGIN, LINE_HEIGHT + 2.0 * MARGIN, &elements))
+}
+
+/// One tablet with a line per row and the columns aligned
+pub fn render_table(rows: &[Vec<Value>], places: usize) -> String {
+    let cells: Vec<Vec<(Vec<String>, f64)>> = rows.iter()
+        .map(|row| row.iter().map(|value| draw_cell(value, places)).collect())
+        .collect();
+    
+    let mut widths: Vec<f64> = Vec::new();
+    for row in &cells {
+        for (column, (_, width)) in row.iter().enumerate() {
+            match widths.get_mut(column) {
+                Some(widest) => *widest = widest.max(*width),
+                None => widths.push(*width),
+            }
+        }
+    }
+    
+    let mut elements = Vec::new();
+    for (line, row) in cells.iter().enumerate() {
+        let y = MARGIN + line as f64 * (LINE_HEIGHT + ROW_GAP);
+        let mut x = MARGIN;
+        for (column, (cell, _)) in row.iter().enumerate() {
+            elements.push(format!("<g transform=\"translate({:.1},{:.1})\">{}</g>", x, y, cell.concat()));
+            x += widths[column] + COLUMN_GAP;
+        }
+    }
+    
+    let gaps = |count: usize| count.saturating_sub(1) as f64;
+    let width = 2.0 * MARGIN + widths.iter().sum::<f64>() + gaps(widths.len()) * COLUMN_GAP;
+    let height = 2.0 * MARGIN + rows.len() as f64 * LINE_HEIGHT + gaps(rows.len()) * ROW_GAP;
+    tablet(width, height, &elements)
+}
+
+/// The standard Old Babylonian reciprocal table: every regular number
+/// from 2 to 1,21 (except 1 itself) with its igi
+pub fn reciprocal_table() -> Vec<Vec<Value>> {
+    (2..=81)
+        .filter(|n| *n != 60)
+        .filter_map(|n| {
+            let number = FloatingNum::from_bigint(&BigInt::from(n));
+            let reciprocal = number.reciprocal()?;
+            Some(vec![Value::Floating(number), Value::Floating(reciprocal)])
+        })
+        .collect()
+}
+
+/// A multiplication table for `head`: its multiples by 1 to 20, 30, 40
+/// and 50, as in the school tablets
+pub fn multiplication_table(head: &FloatingNum) -> Vec<Vec<Value>> {
+    (1..=20)
+        .chain([30, 40, 50])
+        .map(|n| {
+            let n = FloatingNum::from_bigint(&BigInt::from(n));
+            let product = head.mul(&n);
+            vec![Value::Floating(n), Value::Floating(product)]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::parse_number;
+
+    #[test]
+    fn test_standard_stacks() {
+        for place in 1..60u8 {
+            let mut elements = Vec::new();
+            draw_place(&mut elements, place, 0.0, 0.0);
+            assert_eq!(elements.len(), (place / 10 + place % 10) as usize);
+        }
+    }
+
+    #[test]
+    fn test_render_value() {
+        // 1;24,51,10: 1 + 6 + 6 + 1 wedges
+        let svg = render(&parse_number("1;24,51,10").unwrap(), 4).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert_eq!(svg.matches("<path").count(), 14);
+        
+        assert!(render(&Value::Integer(-2), 4).is_none());
+        assert!(render(&Value::Boolean(true), 4).is_none());
+    }
+
+    #[test]
+    fn test_tables() {
+        let reciprocals = reciprocal_table();
+        assert_eq!(reciprocals.len(), 29);
+        assert_eq!(reciprocals[0][1].to_string(), "~30");
+        assert_eq!(reciprocals[28][0].to_string(), "~1,21");
+        assert_eq!(reciprocals[28][1].to_string(), "~44,26,40");
+        
+        let times = multiplication_table(&FloatingNum::from_bigint(&BigInt::from(9)));
+        assert_eq!(times.len(), 23);
+        assert_eq!(times[22][1].to_string(), "~7,30");
+        
+        let svg = render_table(&reciprocals, 4);
+        assert_eq!(svg.matches("<g transform").count(), 58);
+    }
+}
\ No newline at end of file
@@ -1,21 +1,62 @@
+use crate::format::FormatSpec;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     // Identifiers and literals
     Identifier(String),
     Number(String),  // Store as string for parsing into Value later
+    Str(String),     // "text", escapes already resolved
+    FormatStr(Vec<TemplatePart>), // f"side = {x:60.3}"
     
     // Operators
     Plus,        // +
     Minus,       // -
     Asterisk,    // *
     Slash,       // /
+    Caret,       // ^
+    DoubleAsterisk, // **
+    DoubleSlash, // //
+    Percent,     // %
+    PipeForward, // |>
+    DotDot,      // ..
+    DotDotEqual, // ..=
+    
+    // Comparison
+    Equal,       // ==
+    NotEqual,    // !=
+    Less,        // <
+    LessEqual,   // <=
+    Greater,     // >
+    GreaterEqual, // >=
     
     // Assignment
     Assign,      // =
+    FatArrow,    // => between a match pattern and its result
     
-    // Parentheses
+    // Keywords
+    In,          // in (unit conversion)
+    Type,        // type (struct and enum declarations)
+    Match,       // match
+    If,          // if (match guards)
+    Step,        // step (range increments)
+    Try,         // try
+    Catch,       // catch
+    Throw,       // throw
+    
+    // Parentheses and brackets
     LParen,      // (
     RParen,      // )
+    LBracket,    // [
+    RBracket,    // ]
+    LBrace,      // {
+    RBrace,      // }
+    
+    // Punctuation
+    Comma,       // , between items; inside numbers only when digits follow directly
+    Colon,       // : in slices and map entries
+    Dot,         // . for field access
+    Pipe,        // | around lambda parameters
+    Semicolon,   // ; between statements; inside numbers only when digits follow directly
     
     // End of line/statement
     Newline,
@@ -24,18 +65,82 @@ pub enum Token {
     EOF,
 }
 
+/// A piece of an interpolated string
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    Text(String),
+    /// {expression:spec}, with the expression already tokenized
+    Field(Vec<Token>, FormatSpec),
+}
+
+/// Position of a piece of source as character offsets, end exclusive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentKind {
+    Line,        // # to the end of the line
+    Block,       // /* ... */, may nest
+}
+
+/// A comment, kept as trivia beside the token stream so that formatters
+/// and documentation tools can recover it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub kind: CommentKind,
+    /// Source text, delimiters included
+    pub text: String,
+    pub span: Span,
+}
+
 impl std::fmt::Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Token::Identifier(name) => write!(f, "Identifier({})", name),
             Token::Number(value) => write!(f, "Number({})", value),
+            Token::Str(text) => write!(f, "Str({:?})", text),
+            Token::FormatStr(parts) => write!(f, "FormatStr({:?})", parts),
             Token::Plus => write!(f, "+"),
             Token::Minus => write!(f, "-"),
             Token::Asterisk => write!(f, "*"),
             Token::Slash => write!(f, "/"),
+            Token::Caret => write!(f, "^"),
+            Token::DoubleAsterisk => write!(f, "**"),
+            Token::DoubleSlash => write!(f, "//"),
+            Token::Percent => write!(f, "%"),
+            Token::PipeForward => write!(f, "|>"),
+            Token::DotDot => write!(f, ".."),
+            Token::DotDotEqual => write!(f, "..="),
+            Token::Equal => write!(f, "=="),
+            Token::NotEqual => write!(f, "!="),
+            Token::Less => write!(f, "<"),
+            Token::LessEqual => write!(f, "<="),
+            Token::Greater => write!(f, ">"),
+            Token::GreaterEqual => write!(f, ">="),
             Token::Assign => write!(f, "="),
+            Token::FatArrow => write!(f, "=>"),
+            Token::In => write!(f, "in"),
+            Token::Type => write!(f, "type"),
+            Token::Match => write!(f, "match"),
+            Token::If => write!(f, "if"),
+            Token::Step => write!(f, "step"),
+            Token::Try => write!(f, "try"),
+            Token::Catch => write!(f, "catch"),
+            Token::Throw => write!(f, "throw"),
             Token::LParen => write!(f, "("),
             Token::RParen => write!(f, ")"),
+            Token::LBracket => write!(f, "["),
+            Token::RBracket => write!(f, "]"),
+            Token::LBrace => write!(f, "{{"),
+            Token::RBrace => write!(f, "}}"),
+            Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
+            Token::Dot => write!(f, "."),
+            Token::Pipe => write!(f, "|"),
+            Token::Semicolon => write!(f, ";"),
             Token::Newline => write!(f, "newline"),
             Token::EOF => write!(f, "EOF"),
         }
@@ -0,0 +1,279 @@
+use crate::cuneiform;
+use crate::value::Value;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive, Zero};
+use std::collections::HashMap;
+
+const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Fractional places to expand before giving up on finding a period
+const MAX_PLACES: usize = 120;
+
+/// A number split into sexagesimal places for typesetting in Neugebauer
+/// notation, 1;24,51,10
+#[derive(Debug, Clone, PartialEq)]
+pub struct Places {
+    pub negative: bool,
+    /// Integer places, most significant first; [0] for zero
+    pub integer: Vec<u8>,
+    pub fractional: Vec<u8>,
+    /// Index of the first fractional place of a repeating period
+    pub repeat_from: Option<usize>,
+    /// Rounded or cut short; written with ≈
+    pub approximate: bool,
+}
+
+impl Places {
+    /// Places of a number, with floats rounded to `places` fractional places
+    pub fn from_value(value: &Value, places: usize) -> Option<Self> {
+        let (negative, integer, fractional) = value.to_places(places)?;
+        Some(Places {
+            negative,
+            integer: cuneiform::integer_places(&integer),
+            fractional,
+            repeat_from: None,
+            approximate: !value.is_exact(),
+        })
+    }
+    
+    /// Exact expansion of numerator / denominator. Irregular denominators
+    /// give a repeating period, e.g. 1/7 = 0;8,34,17,8,34,17,...; None if
+    /// the denominator is zero.
+    pub fn from_fraction(numerator: &BigInt, denominator: &BigInt) -> Option<Self> {
+        if denominator.is_zero() {
+            return None;
+        }
+        let negative = !numerator.is_zero() && numerator.is_negative() != denominator.is_negative();
+        let denominator = denominator.abs();
+        let (integer, mut remainder) = numerator.abs().div_rem(&denominator);
+        
+        // Long division; the period starts where a remainder comes back
+        let mut fractional = Vec::new();
+        let mut seen = HashMap::new();
+        let mut repeat_from = None;
+        while !remainder.is_zero() && fractional.len() < MAX_PLACES {
+            if let Some(index) = seen.insert(remainder.clone(), fractional.len()) {
+                repeat_from = Some(index);
+                break;
+            }
+            let (place, rest) = (remainder * 60u8).div_rem(&denominator);
+            fractional.push(place.to_u8().unwrap_or(0));
+            remainder = rest;
+        }
+        
+        Some(Places {
+            negative,
+            integer: cuneiform::integer_places(integer.magnitude()),
+            approximate: repeat_from.is_none() && !remainder.is_zero(),
+            fractional,
+            repeat_from,
+        })
+    }
+    
+    /// Fractional places before the period, and the period itself
+    fn split_fraction(&self) -> (&[u8], &[u8]) {
+        self.fractional.split_at(self.repeat_from.unwrap_or(self.fractional.len()))
+    }
+    
+    /// LaTeX math, with the repeating places under an \overline
+    pub fn to_latex(&self) -> String {
+        let join = |places: &[u8]| places.iter().map(|place| place.to_string()).collect::<Vec<_>>().join("{,}");
+        let (fixed, period) = self.split_fraction();
+        
+        let mut text = String::new();
+        if self.approximate {
+            text.push_str("\\approx ");
+        }
+        if self.negative {
+            text.push('-');
+        }
+        text.push_str(&join(&self.integer));
+        if !self.fractional.is_empty() {
+            text.push_str("{;}");
+            text.push_str(&join(fixed));
+        }
+        if !period.is_empty() {
+            if !fixed.is_empty() {
+                text.push_str("{,}");
+            }
+            text.push_str(&format!("\\overline{{{}}}", join(period)));
+        }
+        text
+    }
+    
+    /// A MathML <math> element, with the repeating places overlined
+    pub fn to_mathml(&self) -> String {
+        math_element(&self.mathml_content())
+    }
+    
+    fn mathml_content(&self) -> String {
+        let join = |places: &[u8]| places.iter()
+            .map(|place| format!("<mn>{}</mn>", place))
+            .collect::<Vec<_>>()
+            .join("<mo>,</mo>");
+        let (fixed, period) = self.split_fraction();
+        
+        let mut text = String::new();
+        if self.approximate {
+            text.push_str("<mo>≈</mo>");
+        }
+        if self.negative {
+            text.push_str("<mo>-</mo>");
+        }
+        text.push_str(&join(&self.integer));
+        if !self.fractional.is_empty() {
+            text.push_str("<mo>;</mo>");
+            text.push_str(&join(fixed));
+        }
+        if !period.is_empty() {
+            if !fixed.is_empty() {
+                text.push_str("<mo>,</mo>");
+            }
+            text.push_str(&format!("<mover accent=\"true\"><mrow>{}</mrow><mo>‾</mo></mover>", join(period)));
+        }
+        text
+    }
+}
+
+/// LaTeX math for a value (without the surrounding $...$). Floats are
+/// rounded to `places` sexagesimal places.
+pub fn latex(value: &Value, places: usize) -> String {
+    match value {
+        Value::Quantity(q) => format!("{}\\,\\mathrm{{{}}}", latex(&q.amount, places), q.unit.name),
+        Value::Str(s) => format!("\\text{{{}}}", escape_latex(s)),
+        _ => match Places::from_value(value, places) {
+            Some(number) => number.to_latex(),
+            None => format!("\\mathrm{{{}}}", escape_latex(&value.to_string())),
+        },
+    }
+}
+
+/// A MathML <math> element for a value. Floats are rounded to `places`
+/// sexagesimal places.
+pub fn mathml(value: &Value, places: usize) -> String {
+    math_element(&mathml_content(value, places))
+}
+
+fn mathml_content(value: &Value, places: usize) -> String {
+    match value {
+        Value::Quantity(q) => format!(
+            "{}<mspace width=\"0.17em\"/><mi mathvariant=\"normal\">{}</mi>",
+            mathml_content(&q.amount, places), escape_xml(q.unit.name)
+        ),
+        _ => match Places::from_value(value, places) {
+            Some(number) => number.mathml_content(),
+            None => format!("<mtext>{}</mtext>", escape_xml(&value.to_string())),
+        },
+    }
+}
+
+/// A tabular environment with one value per cell, as in the tables of
+/// Neugebauer and Sachs
+pub fn latex_table(rows: &[Vec<Value>], places: usize) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut text = format!("\\begin{{tabular}}{{{}}}\n", "r".repeat(columns));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|value| format!("${}$", latex(value, places))).collect();
+        text.push_str(&format!("{} \\\\\n", cells.join(" & ")));
+    }
+    text.push_str("\\end{tabular}");
+    text
+}
+
+/// A MathML <mtable> with one value per cell
+pub fn mathml_table(rows: &[Vec<Value>], places: usize) -> String {
+    let rows: String = rows.iter()
+        .map(|row| {
+            let cells: String = row.iter()
+                .map(|value| format!("<mtd>{}</mtd>", mathml_content(value, places)))
+                .collect();
+            format!("<mtr>{}</mtr>", cells)
+        })
+        .collect();
+    math_element(&format!("<mtable>{}</mtable>", rows))
+}
+
+fn math_element(content: &str) -> String {
+    format!("<math xmlns=\"{}\">{}</math>", MATHML_NAMESPACE, content)
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::parse_number;
+
+    #[test]
+    fn test_latex_neugebauer_notation() {
+        let value = parse_number("1;24,51,10").unwrap();
+        
+        assert_eq!(latex(&value, 4), "1{;}24{,}51{,}10");
+        assert_eq!(latex(&Value::Integer(3690), 4), "1{,}1{,}30");
+        assert_eq!(latex(&Value::Float(1.5), 4), "\\approx 1{;}30");
+        assert_eq!(latex(&Value::Str("5%".to_string()), 4), "\\text{5\\%}");
+    }
+
+    #[test]
+    fn test_repeating_places() {
+        let seventh = Places::from_fraction(&BigInt::from(1), &BigInt::from(7)).unwrap();
+        assert_eq!(seventh.fractional, vec![8, 34, 17]);
+        assert_eq!(seventh.to_latex(), "0{;}\\overline{8{,}34{,}17}");
+        
+        let fourteenth = Places::from_fraction(&BigInt::from(-1), &BigInt::from(14)).unwrap();
+        assert_eq!(fourteenth.to_latex(), "-0{;}4{,}\\overline{17{,}8{,}34}");
+        
+        let regular = Places::from_fraction(&BigInt::from(3), &BigInt::from(2)).unwrap();
+        assert_eq!(regular.to_latex(), "1{;}30");
+        assert!(Places::from_fraction(&BigInt::from(1), &BigInt::zero()).is_none());
+    }
+
+    #[test]
+    fn test_mathml() {
+        let seventh = Places::from_fraction(&BigInt::from(1), &BigInt::from(7)).unwrap();
+        
+        assert_eq!(
+            mathml(&parse_number("1;30").unwrap(), 4),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mn>1</mn><mo>;</mo><mn>30</mn></math>"
+        );
+        assert!(seventh.to_mathml().contains(
+            "<mover accent=\"true\"><mrow><mn>8</mn><mo>,</mo><mn>34</mn><mo>,</mo><mn>17</mn></mrow><mo>‾</mo></mover>"
+        ));
+    }
+
+    #[test]
+    fn test_latex_table() {
+        let rows = vec![
+            vec![Value::Integer(2), Value::Integer(30)],
+            vec![Value::Integer(8), parse_number("7;30").unwrap()],
+        ];
+        
+        assert_eq!(
+            latex_table(&rows, 4),
+            "\\begin{tabular}{rr}\n$2$ & $30$ \\\\\n$8$ & $7{;}30$ \\\\\n\\end{tabular}"
+        );
+    }
+}
\ No newline at end of file
@@ -1,5 +1,15 @@
 use std::fmt;
+use std::cmp::Ordering;
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive, Zero};
 use thiserror::Error;
+use crate::ast::Expression;
+use crate::cuneiform;
+use crate::floating::{parse_floating, FloatingNum};
+use crate::interpreter::Environment;
+use crate::metrology::{self, Quantity};
+use crate::radix::{parse_radix, RadixNum};
 
 #[derive(Error, Debug)]
 pub enum NumberError {
@@ -16,74 +26,578 @@ pub enum NumberError {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Integer(i64),
+    BigInt(BigInt), // only for integers outside the i64 range
     Float(f64),
     Sexagesimal(SexagesimalNum),
+    Radix(RadixNum),
+    Floating(FloatingNum),
+    Quantity(Quantity),
+    Boolean(bool),
+    Str(String),
+    List(Vec<Value>),
+    /// (q, r): a fixed group of values, as returned by divmod
+    Tuple(Vec<Value>),
+    /// {line: 3, width: 1;59}, keys kept in the order written
+    Map(Vec<(String, Value)>),
+    Record(Record),
+    Function(Function),
+    Error(ErrorValue),
 }
 
+/// A caught or thrown error: its kind, such as DivisionByZero, and the
+/// message it would have stopped the program with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorValue {
+    pub kind: String,
+    pub message: String,
+}
+
+/// A value of a declared type: a struct, or one variant of an enum
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub type_name: String,
+    /// The type name again for structs
+    pub variant: String,
+    pub fields: Vec<(String, Value)>,
+}
+
+/// A value that can be called: a builtin such as igi, named by its
+/// identifier, or a lambda such as |x| x * 2
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {
+    Builtin(String),
+    /// Builds a record from its field values, in declaration order
+    Constructor {
+        type_name: String,
+        variant: String,
+        fields: Vec<String>,
+    },
+    Lambda {
+        params: Vec<String>,
+        body: Box<Expression>,
+        /// Variables as they were where the lambda was written
+        captured: Environment,
+    },
+}
+
+impl Value {
+    /// Plain numbers; quantities carry a unit and are handled apart
+    pub fn is_number(&self) -> bool {
+        !matches!(
+            self,
+            Value::Boolean(_) | Value::Quantity(_) | Value::Str(_) | Value::Function(_) | Value::Error(_) |
+            Value::List(_) | Value::Tuple(_) | Value::Map(_) | Value::Record(_)
+        )
+    }
+    
+    /// Scheme-style exactness: integers are exact, floats never are, and
+    /// sexagesimals are exact until something rounds them
+    pub fn is_exact(&self) -> bool {
+        match self {
+            Value::Integer(_) | Value::BigInt(_) | Value::Radix(_) | Value::Floating(_) => true,
+            Value::Boolean(_) | Value::Str(_) | Value::Function(_) | Value::Error(_) => true,
+            Value::Float(_) => false,
+            Value::Sexagesimal(sex) => sex.exact,
+            Value::Quantity(q) => q.amount.is_exact(),
+            Value::List(items) | Value::Tuple(items) => items.iter().all(Value::is_exact),
+            Value::Map(entries) | Value::Record(Record { fields: entries, .. }) => {
+                entries.iter().all(|(_, value)| value.is_exact())
+            }
+        }
+    }
+    
+    /// Wraps an arbitrary-precision integer, narrowing it back to
+    /// `Integer` whenever it fits in an i64
+    pub fn from_bigint(n: BigInt) -> Self {
+        match i64::try_from(&n) {
+            Ok(i) => Value::Integer(i),
+            Err(_) => Value::BigInt(n),
+        }
+    }
+    
+    /// The value under `key` in a map or record, for host code reading
+    /// results
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(entries) | Value::Record(Record { fields: entries, .. }) => {
+                entries.iter().find(|(name, _)| name == key).map(|(_, value)| value)
+            }
+            _ => None,
+        }
+    }
+    
+    /// As written inside a collection, where strings are quoted
+    fn quoted(&self) -> String {
+        match self {
+            Value::Str(s) => format!("{:?}", s),
+            _ => self.to_string(),
+        }
+    }
+    
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Value::Integer(i) => Some(BigInt::from(*i)),
+            Value::BigInt(n) => Some(n.clone()),
+            Value::Radix(r) => r.to_bigint(),
+            _ => None,
+        }
+    }
+    
+    /// Numeric value as a float; NaN for values that are not numbers
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Value::Integer(i) => *i as f64,
+            Value::BigInt(n) => n.to_f64().unwrap_or(f64::NAN),
+            Value::Radix(r) => r.to_f64(),
+            Value::Float(n) => *n,
+            Value::Sexagesimal(sex) => sex.to_f64(),
+            // A floating value has no absolute magnitude
+            Value::Floating(_) | Value::Quantity(_) => f64::NAN,
+            Value::Boolean(_) | Value::Str(_) | Value::Function(_) | Value::Error(_) => f64::NAN,
+            Value::List(_) | Value::Tuple(_) | Value::Map(_) | Value::Record(_) => f64::NAN,
+        }
+    }
+    
+    /// Sign, integer magnitude and fractional places of a number, with
+    /// floats rounded to `places` sexagesimal places
+    pub fn to_places(&self, places: usize) -> Option<(bool, BigUint, Vec<u8>)> {
+        match self {
+            Value::Radix(r) if r.to_bigint().is_none() => {
+                let sex = r.to_sexagesimal(places, Rounding::HalfEven)?;
+                Some((sex.negative, BigUint::from(sex.integer_part), sex.fractional_places))
+            }
+            Value::Integer(_) | Value::BigInt(_) | Value::Radix(_) => {
+                let n = self.to_bigint()?;
+                Some((n.is_negative(), n.magnitude().clone(), Vec::new()))
+            }
+            Value::Float(n) => {
+                let sex = SexagesimalNum::from_f64_rounded(*n, places, Rounding::HalfEven)?;
+                Some((sex.negative, BigUint::from(sex.integer_part), sex.fractional_places))
+            }
+            Value::Sexagesimal(sex) => {
+                Some((sex.negative, BigUint::from(sex.integer_part), sex.fractional_places.clone()))
+            }
+            // Written as on a tablet, without a fractional boundary
+            Value::Floating(x) => Some((x.negative, x.mantissa().magnitude().clone(), Vec::new())),
+            Value::Quantity(_) => None,
+            Value::Boolean(_) | Value::Str(_) | Value::Function(_) | Value::Error(_) => None,
+            Value::List(_) | Value::Tuple(_) | Value::Map(_) | Value::Record(_) => None,
+        }
+    }
+    
+    /// Writes numbers in cuneiform with `separator` between places.
+    /// Floats are first rounded to `places` sexagesimal places.
+    pub fn to_cuneiform(&self, separator: &str, places: usize) -> String {
+        let Some((negative, integer, fractional)) = self.to_places(places) else {
+            return self.to_string();
+        };
+        let text = cuneiform::format_number(negative, &integer, &fractional, separator);
+        if self.is_exact() { text } else { format!("≈{}", text) }
+    }
+    
+    /// Writes numbers as ATF transliteration, e.g. 1(diš) 2(u) 3(diš).
+    /// Floats are first rounded to `places` sexagesimal places.
+    pub fn to_transliteration(&self, places: usize) -> String {
+        let Some((negative, integer, fractional)) = self.to_places(places) else {
+            return self.to_string();
+        };
+        let text = format_transliteration(negative, &cuneiform::integer_places(&integer), &fractional);
+        if self.is_exact() { text } else { format!("≈{}", text) }
+    }
+}
+
+/// How results are cut down to a fixed number of sexagesimal places
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounding {
+    /// Round to nearest, ties to an even last place
+    HalfEven,
+    /// Drop the extra places (round towards zero)
+    Truncate,
+    /// Round towards negative infinity
+    Floor,
+}
+
+impl Rounding {
+    /// Divides by a positive denominator, rounding the quotient
+    pub fn divide(self, numerator: &BigInt, denominator: &BigInt) -> BigInt {
+        let (quotient, remainder) = numerator.div_mod_floor(denominator);
+        
+        match self {
+            Rounding::Floor => quotient,
+            Rounding::Truncate => {
+                if numerator.is_negative() && !remainder.is_zero() {
+                    quotient + 1
+                } else {
+                    quotient
+                }
+            }
+            Rounding::HalfEven => match (remainder * BigInt::from(2)).cmp(denominator) {
+                Ordering::Less => quotient,
+                Ordering::Greater => quotient + 1,
+                Ordering::Equal if quotient.is_even() => quotient,
+                Ordering::Equal => quotient + 1,
+            },
+        }
+    }
+}
+
+/// A sexagesimal number in sign-magnitude form: the sign lives in
+/// `negative` and the parts are magnitudes, so -1;30 is -(1 + 30/60)
 #[derive(Debug, Clone, PartialEq)]
 pub struct SexagesimalNum {
-    pub integer_part: i64,
-    pub fractional_part: i64, // stored as sixtieths (0-59)
-    pub has_fraction: bool,
+    pub negative: bool,
+    pub integer_part: u64,
+    /// Places after the ';', most significant first, each 0-59.
+    /// Trailing zero places are never stored.
+    pub fractional_places: Vec<u8>,
+    /// False once the value has been rounded or derived from a float
+    pub exact: bool,
 }
 
 impl SexagesimalNum {
+    /// Builds `integer;fractional`, taking the sign from the integer part
     pub fn new(integer: i64, fractional: i64) -> Result<Self, NumberError> {
-        if fractional < 0 || fractional >= 60 {
-            return Err(NumberError::InvalidFormat(
+        let fractional = u8::try_from(fractional)
+            .ok()
+            .filter(|f| *f < 60)
+            .ok_or_else(|| NumberError::InvalidFormat(
                 format!("Fractional part must be between 0 and 59, got {}", fractional)
-            ));
+            ))?;
+        
+        Ok(SexagesimalNum::from_parts(integer < 0, integer.unsigned_abs(), vec![fractional]))
+    }
+    
+    /// Builds a number from its sign and magnitudes; needed for values
+    /// such as -0;30 whose integer part cannot carry the sign
+    pub fn from_parts(negative: bool, integer_part: u64, mut fractional_places: Vec<u8>) -> Self {
+        while fractional_places.last() == Some(&0) {
+            fractional_places.pop();
         }
+        let is_zero = integer_part == 0 && fractional_places.is_empty();
         
-        Ok(SexagesimalNum {
-            integer_part: integer,
-            fractional_part: fractional,
-            has_fraction: fractional != 0,
-        })
+        SexagesimalNum {
+            negative: negative && !is_zero,
+            integer_part,
+            fractional_places,
+            exact: true,
+        }
+    }
+    
+    pub fn with_exactness(mut self, exact: bool) -> Self {
+        self.exact = exact;
+        self
+    }
+    
+    pub fn from_integer(value: i64) -> Self {
+        SexagesimalNum::from_parts(value < 0, value.unsigned_abs(), Vec::new())
+    }
+    
+    pub fn places(&self) -> usize {
+        self.fractional_places.len()
     }
     
     pub fn to_f64(&self) -> f64 {
-        self.integer_part as f64 + (self.fractional_part as f64 / 60.0)
+        let mut magnitude = self.integer_part as f64;
+        let mut unit = 1.0;
+        for place in &self.fractional_places {
+            unit /= 60.0;
+            magnitude += *place as f64 * unit;
+        }
+        if self.negative { -magnitude } else { magnitude }
+    }
+    
+    /// Cuneiform wedge groups, one per place, joined by `separator`
+    pub fn to_cuneiform(&self, separator: &str) -> String {
+        cuneiform::format_number(
+            self.negative,
+            &BigUint::from(self.integer_part),
+            &self.fractional_places,
+            separator,
+        )
     }
     
-    pub fn from_f64(value: f64) -> Self {
-        let integer_part = value.floor() as i64;
-        let fractional = ((value - integer_part as f64) * 60.0).round() as i64;
+    /// Converts a float, keeping `places` fractional places. None for
+    /// non-finite floats and results whose integer part leaves the u64 range.
+    pub fn from_f64_rounded(value: f64, places: usize, rounding: Rounding) -> Option<Self> {
+        if !value.is_finite() || places as u64 > MAX_POWER_BITS / 6 {
+            return None;
+        }
         
-        SexagesimalNum {
-            integer_part,
-            fractional_part: fractional,
-            has_fraction: fractional != 0,
+        // A float is exactly sign * mantissa * 2^exponent, so it can be
+        // scaled by 60^places without losing anything before rounding
+        let (mantissa, exponent, sign) = num_traits::Float::integer_decode(value);
+        let numerator = BigInt::from(mantissa) * sign * BigInt::from(60).pow(places as u32);
+        let scaled = if exponent >= 0 {
+            numerator << exponent as usize
+        } else {
+            rounding.divide(&numerator, &(BigInt::from(1) << exponent.unsigned_abs() as usize))
+        };
+        SexagesimalNum::from_scaled(&scaled, places).map(|sex| sex.with_exactness(false))
+    }
+    
+    /// The value multiplied by 60^places, as an exact integer.
+    /// `places` must be at least `self.places()`.
+    pub fn to_scaled(&self, places: usize) -> BigInt {
+        let mut scaled = BigInt::from(self.integer_part);
+        for index in 0..places {
+            let place = self.fractional_places.get(index).copied().unwrap_or(0);
+            scaled = scaled * 60 + place;
+        }
+        if self.negative { -scaled } else { scaled }
+    }
+    
+    /// Inverse of `to_scaled`; None if the integer part does not fit in a u64
+    pub fn from_scaled(scaled: &BigInt, places: usize) -> Option<Self> {
+        let mut magnitude = scaled.magnitude().clone();
+        let mut fractional_places = vec![0; places];
+        for place in fractional_places.iter_mut().rev() {
+            let (rest, digit) = magnitude.div_rem(&BigUint::from(60u8));
+            *place = digit.to_u8()?;
+            magnitude = rest;
+        }
+        let integer_part = magnitude.to_u64()?;
+        
+        Some(SexagesimalNum::from_parts(scaled.is_negative(), integer_part, fractional_places))
+    }
+    
+    pub fn negated(&self) -> Self {
+        SexagesimalNum::from_parts(!self.negative, self.integer_part, self.fractional_places.clone())
+            .with_exactness(self.exact)
+    }
+    
+    /// Rounds to at most `places` fractional places
+    pub fn round_to(&self, places: usize, rounding: Rounding) -> Option<Self> {
+        if self.places() <= places {
+            return Some(self.clone());
+        }
+        let excess = (self.places() - places) as u32;
+        let (scaled, unit) = (self.to_scaled(self.places()), BigInt::from(60).pow(excess));
+        let exact = self.exact && (&scaled % &unit).is_zero();
+        SexagesimalNum::from_scaled(&rounding.divide(&scaled, &unit), places)
+            .map(|sex| sex.with_exactness(exact))
+    }
+    
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let places = self.places().max(other.places());
+        SexagesimalNum::from_scaled(&(self.to_scaled(places) + other.to_scaled(places)), places)
+            .map(|sex| sex.with_exactness(self.exact && other.exact))
+    }
+    
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.checked_add(&other.negated())
+    }
+    
+    /// Exact product; the result has as many places as both factors together
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        let product = self.to_scaled(self.places()) * other.to_scaled(other.places());
+        SexagesimalNum::from_scaled(&product, self.places() + other.places())
+            .map(|sex| sex.with_exactness(self.exact && other.exact))
+    }
+    
+    /// Quotient rounded to `places` fractional places; None when dividing
+    /// by zero or when the integer part overflows
+    pub fn checked_div(&self, other: &Self, places: usize, rounding: Rounding) -> Option<Self> {
+        let common = self.places().max(other.places());
+        let divisor = other.to_scaled(common);
+        if divisor.is_zero() {
+            return None;
+        }
+        
+        // (a / b) * 60^places, with the sign moved onto the numerator
+        let numerator = self.to_scaled(common) * BigInt::from(60).pow(places as u32);
+        let (numerator, divisor) = if divisor.is_negative() {
+            (-numerator, -divisor)
+        } else {
+            (numerator, divisor)
+        };
+        let exact = self.exact && other.exact && (&numerator % &divisor).is_zero();
+        SexagesimalNum::from_scaled(&rounding.divide(&numerator, &divisor), places)
+            .map(|sex| sex.with_exactness(exact))
+    }
+    
+    /// Exact non-negative integer power. None when the result would be
+    /// too large to compute.
+    pub fn checked_pow(&self, exp: u32) -> Option<Self> {
+        let scaled = self.to_scaled(self.places());
+        let places = self.places().checked_mul(exp as usize)
+            .filter(|places| *places as u64 <= MAX_POWER_BITS / 6)?;
+        if !power_fits(&scaled, exp) {
+            return None;
+        }
+        let power = scaled.pow(exp);
+        SexagesimalNum::from_scaled(&power, places)
+            .map(|sex| sex.with_exactness(self.exact))
+    }
+    
+    /// Floor division: a whole quotient and a remainder with the sign of
+    /// the divisor, both exact. None when dividing by zero.
+    pub fn div_rem_floor(&self, other: &Self) -> Option<(Self, Self)> {
+        let places = self.places().max(other.places());
+        let divisor = other.to_scaled(places);
+        if divisor.is_zero() {
+            return None;
         }
+        
+        let (quotient, remainder) = self.to_scaled(places).div_mod_floor(&divisor);
+        let exact = self.exact && other.exact;
+        Some((
+            SexagesimalNum::from_scaled(&quotient, 0)?.with_exactness(exact),
+            SexagesimalNum::from_scaled(&remainder, places)?.with_exactness(exact),
+        ))
     }
 }
 
 impl fmt::Display for Value {
+    /// Inexact values are marked with a leading ≈. The alternate form
+    /// (`{:#}`) writes numbers in cuneiform; floats keep the requested
+    /// precision in sexagesimal places, 4 by default.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.to_cuneiform(cuneiform::PLACE_SEPARATOR, f.precision().unwrap_or(4)));
+        }
+        
+        // Quantities and collections are marked through their contents
+        if !self.is_exact() && !matches!(
+            self,
+            Value::Quantity(_) | Value::List(_) | Value::Tuple(_) | Value::Map(_) | Value::Record(_)
+        ) {
+            write!(f, "≈")?;
+        }
+        
         match self {
             Value::Integer(i) => write!(f, "{}", i),
+            Value::BigInt(n) => write!(f, "{}", n),
             Value::Float(n) => write!(f, "{}", n),
             Value::Sexagesimal(sex) => write!(f, "{}", sex),
+            Value::Radix(r) => write!(f, "{}", r),
+            Value::Floating(x) => write!(f, "{}", x),
+            Value::Quantity(q) => write!(f, "{}", q),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::List(items) => {
+                // Strings inside a list are quoted: ["a", 1;30]
+                let items: Vec<String> = items.iter().map(Value::quoted).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            // A single item keeps its comma: (x,)
+            Value::Tuple(items) if items.len() == 1 => write!(f, "({},)", items[0].quoted()),
+            Value::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(Value::quoted).collect();
+                write!(f, "({})", items.join(", "))
+            }
+            Value::Map(entries) => {
+                // Keys that could not be written bare are quoted too
+                let entries: Vec<String> = entries.iter()
+                    .map(|(key, value)| {
+                        let bare = key.chars().next().is_some_and(|ch| ch.is_alphabetic() || ch == '_')
+                            && key.chars().all(|ch| ch.is_alphanumeric() || ch == '_');
+                        let key = if bare { key.clone() } else { format!("{:?}", key) };
+                        format!("{}: {}", key, value.quoted())
+                    })
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            Value::Record(record) => write!(f, "{}", record),
+            Value::Error(error) => write!(f, "{}", error),
+            Value::Function(function) => write!(f, "{}", function),
+        }
+    }
+}
+
+impl fmt::Display for Record {
+    /// Rect(width: 2, length: 3), or just the name without fields
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.fields.is_empty() {
+            return write!(f, "{}", self.variant);
+        }
+        let fields: Vec<String> = self.fields.iter()
+            .map(|(name, value)| format!("{}: {}", name, value.quoted()))
+            .collect();
+        write!(f, "{}({})", self.variant, fields.join(", "))
+    }
+}
+
+impl fmt::Display for ErrorValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<error {}: {}>", self.kind, self.message)
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Function::Builtin(name) => write!(f, "<builtin {}>", name),
+            Function::Constructor { variant, .. } => write!(f, "<constructor {}>", variant),
+            Function::Lambda { params, .. } => write!(f, "<lambda |{}|>", params.join(", ")),
         }
     }
 }
 
 impl fmt::Display for SexagesimalNum {
+    /// Neugebauer notation: 1;24,51,10, or cuneiform with `{:#}`
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.has_fraction {
-            write!(f, "{};{:02}", self.integer_part, self.fractional_part)
-        } else {
-            write!(f, "{}", self.integer_part)
+        if f.alternate() {
+            return write!(f, "{}", self.to_cuneiform(cuneiform::PLACE_SEPARATOR));
+        }
+        
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{}{}", sign, self.integer_part)?;
+        
+        for (index, place) in self.fractional_places.iter().enumerate() {
+            let separator = if index == 0 { ';' } else { ',' };
+            write!(f, "{}{:02}", separator, place)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Rounding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rounding::HalfEven => write!(f, "half-even"),
+            Rounding::Truncate => write!(f, "truncate"),
+            Rounding::Floor => write!(f, "floor"),
         }
     }
 }
 
+/// The largest power, in bits, that is computed exactly
+pub const MAX_POWER_BITS: u64 = 1 << 20;
+
+/// Whether base ^ exp stays within MAX_POWER_BITS. Powers of 0, 1 and -1
+/// are always small.
+pub fn power_fits(base: &BigInt, exp: u32) -> bool {
+    let bits = base.magnitude().bits();
+    bits <= 1 || bits.saturating_mul(exp as u64) <= MAX_POWER_BITS
+}
+
 /// Parses a number string, detecting base-10 and sexagesimal formats
 pub fn parse_number(s: &str) -> Result<Value, NumberError> {
     if s.is_empty() {
         return Err(NumberError::EmptyNumber);
     }
     
+    // Radix literals: 20r1,3,7, or 20r0;(6,13) with a repeating period
+    let radix_spec = s.trim_start_matches('-').split_once('r').map(|(spec, _)| spec);
+    if radix_spec.is_some_and(|spec| !spec.is_empty() && spec.chars().all(|c| c.is_ascii_digit() || c == '_')) {
+        return parse_radix(s).map(Value::Radix);
+    }
+    
+    // ATF transliteration: 1(diš) 2(u) 3(diš)
+    if s.contains('(') {
+        return parse_transliteration(s);
+    }
+    
+    // Floating literals: ~1,30
+    if let Some(places) = s.strip_prefix('~') {
+        return parse_floating(places).map(Value::Floating);
+    }
+    
+    // Angles and times: 12°30'15" and 1:02:03.5
+    if s.contains('°') || s.contains(':') {
+        return parse_angle_or_time(s);
+    }
+    
     // Check for sexagesimal notation (using ; as separator)
     if s.contains(';') {
         return parse_sexagesimal(s);
@@ -112,10 +626,13 @@ fn parse_base10(s: &str) -> Result<Value, NumberError> {
             Err(_) => Err(NumberError::InvalidFormat(s.to_string())),
         }
     } else {
-        // Parse as integer
+        // Parse as integer, falling back to a big integer for long literals
         match s.parse::<i64>() {
             Ok(i) => Ok(Value::Integer(i)),
-            Err(_) => Err(NumberError::InvalidFormat(s.to_string())),
+            Err(_) => match s.parse::<BigInt>() {
+                Ok(n) => Ok(Value::from_bigint(n)),
+                Err(_) => Err(NumberError::InvalidFormat(s.to_string())),
+            },
         }
     }
 }
@@ -129,30 +646,36 @@ fn parse_sexagesimal(s: &str) -> Result<Value, NumberError> {
         ));
     }
     
-    let integer_part = parts[0].parse::<i64>()
-        .map_err(|_| NumberError::InvalidFormat(parts[0].to_string()))?;
+    let (negative, integer_part, fractional_places) = parse_sexagesimal_parts(parts[0], parts[1])?;
     
-    let fractional_part = parts[1].parse::<i64>()
-        .map_err(|_| NumberError::InvalidFormat(parts[1].to_string()))?;
+    Ok(Value::Sexagesimal(SexagesimalNum::from_parts(negative, integer_part, fractional_places)))
+}
+
+/// Splits `[-]integer` and comma-separated `fractional` places into sign
+/// and magnitudes. The sign applies to the whole number, so "-0;30" is -0.5.
+fn parse_sexagesimal_parts(integer: &str, fractional: &str) -> Result<(bool, u64, Vec<u8>), NumberError> {
+    let (negative, digits) = match integer.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, integer),
+    };
     
-    if fractional_part < 0 || fractional_part >= 60 {
-        return Err(NumberError::InvalidFormat(
-            format!("Fractional part must be between 0 and 59, got {}", fractional_part)
-        ));
-    }
+    let integer_part = digits.parse::<u64>()
+        .map_err(|_| NumberError::InvalidFormat(integer.to_string()))?;
     
-    // Handle negative sexagesimal numbers
-    let sexagesimal = if integer_part < 0 {
-        SexagesimalNum {
-            integer_part,
-            fractional_part: -fractional_part, // Make fractional part negative too
-            has_fraction: fractional_part != 0,
+    let mut fractional_places = Vec::new();
+    for place in fractional.split(',') {
+        let place = place.parse::<u8>()
+            .map_err(|_| NumberError::InvalidFormat(place.to_string()))?;
+        
+        if place >= 60 {
+            return Err(NumberError::InvalidFormat(
+                format!("Fractional part must be between 0 and 59, got {}", place)
+            ));
         }
-    } else {
-        SexagesimalNum::new(integer_part, fractional_part)?
-    };
+        fractional_places.push(place);
+    }
     
-    Ok(Value::Sexagesimal(sexagesimal))
+    Ok((negative, integer_part, fractional_places))
 }
 
 fn parse_sexagesimal_comma(s: &str) -> Result<Value, NumberError> {
@@ -164,19 +687,9 @@ fn parse_sexagesimal_comma(s: &str) -> Result<Value, NumberError> {
         parse_base10(parts[0])
     } else if parts.len() == 2 {
         // Two parts: integer and fractional (base-60)
-        let integer_part = parts[0].parse::<i64>()
-            .map_err(|_| NumberError::InvalidFormat(parts[0].to_string()))?;
+        let (negative, integer_part, fractional_places) = parse_sexagesimal_parts(parts[0], parts[1])?;
         
-        let fractional_part = parts[1].parse::<i64>()
-            .map_err(|_| NumberError::InvalidFormat(parts[1].to_string()))?;
-        
-        if fractional_part < 0 || fractional_part >= 60 {
-            return Err(NumberError::InvalidFormat(
-                format!("Fractional part must be between 0 and 59, got {}", fractional_part)
-            ));
-        }
-        
-        let value = integer_part as f64 + (fractional_part as f64 / 60.0);
+        let value = SexagesimalNum::from_parts(negative, integer_part, fractional_places).to_f64();
         Ok(Value::Float(value)) // Store as float for now, will convert to Sexagesimal later
     } else {
         // Multiple parts - full base-60 positional notation (for future)
@@ -186,6 +699,196 @@ fn parse_sexagesimal_comma(s: &str) -> Result<Value, NumberError> {
     }
 }
 
+/// Value of a transliterated number sign. diš (1) and u (10) build
+/// base-60 places; geš₂, geš'u, šar₂ and šar'u are absolute units.
+/// The C-ATF spellings (disz, gesz2, szar2) are accepted too.
+pub fn transliteration_unit(name: &str) -> Option<u64> {
+    let name = name.replace("sz", "š").replace('₂', "2");
+    match name.as_str() {
+        "diš" => Some(1),
+        "u" => Some(10),
+        "geš2" => Some(60),
+        "geš'u" => Some(600),
+        "šar2" => Some(3600),
+        "šar'u" => Some(36000),
+        _ => None,
+    }
+}
+
+/// Parses ATF number transliteration such as `1(diš) 2(u) 3(diš)` (83).
+///
+/// A diš or u sign that does not continue the current place (a u after
+/// a diš, or a diš after a diš) starts the next place. Fraction signs
+/// like `1/2(diš)` and `2/3(diš)` add sixtieths, and a ';' marks where
+/// the fractional places begin.
+pub fn parse_transliteration(s: &str) -> Result<Value, NumberError> {
+    let invalid = || NumberError::InvalidFormat(s.to_string());
+    let (negative, body) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    
+    let mut absolute = BigInt::zero();
+    let mut integer_places: Vec<u8> = Vec::new();
+    let mut fractional_places: Vec<u8> = Vec::new();
+    let mut sixtieths: u64 = 0;
+    let mut in_fraction = false;
+    let mut last_unit: Option<u64> = None;
+    
+    let body = body.replace(';', " ; ");
+    for sign in body.split_whitespace() {
+        if sign == ";" {
+            if in_fraction {
+                return Err(invalid());
+            }
+            in_fraction = true;
+            last_unit = None;
+            continue;
+        }
+        
+        let (count, rest) = sign.split_once('(').ok_or_else(invalid)?;
+        let unit = rest.strip_suffix(')').and_then(transliteration_unit).ok_or_else(invalid)?;
+        
+        // Fraction signs: numerator/denominator(diš), in whole sixtieths
+        if let Some((numerator, denominator)) = count.split_once('/') {
+            let numerator: u64 = numerator.parse().map_err(|_| invalid())?;
+            let denominator: u64 = denominator.parse().map_err(|_| invalid())?;
+            if unit != 1 || denominator == 0 || 60 % denominator != 0 || numerator >= denominator {
+                return Err(invalid());
+            }
+            sixtieths += numerator * 60 / denominator;
+            continue;
+        }
+        
+        let count: u64 = count.parse().map_err(|_| invalid())?;
+        match unit {
+            1 | 10 => {
+                if count > if unit == 1 { 9 } else { 5 } {
+                    return Err(invalid());
+                }
+                let places = if in_fraction { &mut fractional_places } else { &mut integer_places };
+                if last_unit.is_none_or(|last| unit >= last) {
+                    places.push(0);
+                }
+                *places.last_mut().unwrap() += (count * unit) as u8;
+                last_unit = Some(unit);
+            }
+            _ if !in_fraction => absolute += BigInt::from(count) * unit,
+            _ => return Err(invalid()),
+        }
+    }
+    
+    if integer_places.is_empty() && fractional_places.is_empty() && absolute.is_zero() && sixtieths == 0 {
+        return Err(NumberError::EmptyNumber);
+    }
+    
+    let integer = integer_places.iter()
+        .fold(BigInt::zero(), |total, place| total * 60 + *place) + absolute;
+    
+    if sixtieths > 0 {
+        if fractional_places.is_empty() {
+            fractional_places.push(0);
+        }
+        let first = fractional_places[0] as u64 + sixtieths;
+        if first >= 60 {
+            return Err(invalid());
+        }
+        fractional_places[0] = first as u8;
+    }
+    
+    if fractional_places.is_empty() {
+        return Ok(Value::from_bigint(if negative { -integer } else { integer }));
+    }
+    let integer_part = integer.to_u64().ok_or_else(invalid)?;
+    Ok(Value::Sexagesimal(SexagesimalNum::from_parts(negative, integer_part, fractional_places)))
+}
+
+/// Parses degrees-minutes-seconds (12°30'15") into a quantity of
+/// degrees and hours-minutes-seconds (1:02:03.5) into one of hours.
+/// The amount is an exact sexagesimal; decimal seconds convert exactly
+/// because 10 divides a power of 60.
+fn parse_angle_or_time(s: &str) -> Result<Value, NumberError> {
+    let invalid = || NumberError::InvalidFormat(s.to_string());
+    
+    let (components, unit) = match s.split_once('°') {
+        Some((degrees, rest)) => {
+            // Minutes and seconds need their marks: 12°30 is ambiguous
+            let (minutes, seconds) = match rest.split_once(['\'', '′']) {
+                Some((minutes, seconds)) => (Some(minutes), seconds),
+                None => (None, rest),
+            };
+            let seconds = match seconds {
+                "" => None,
+                seconds => Some(seconds.strip_suffix(['"', '″']).ok_or_else(invalid)?),
+            };
+            let mut components = vec![degrees];
+            if minutes.is_some() || seconds.is_some() {
+                components.push(minutes.unwrap_or("0"));
+            }
+            components.extend(seconds);
+            (components, "deg")
+        }
+        None => (s.split(':').collect::<Vec<_>>(), "h"),
+    };
+    
+    let (negative, first) = match components[0].strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, components[0]),
+    };
+    let integer_part = first.parse::<u64>().map_err(|_| invalid())?;
+    
+    let mut places = Vec::new();
+    let mut decimals = "";
+    for (index, component) in components.iter().enumerate().skip(1) {
+        let (whole, fraction) = component.split_once('.').unwrap_or((component, ""));
+        if !fraction.is_empty() && index != components.len() - 1 {
+            return Err(invalid());
+        }
+        let place = whole.parse::<u8>().ok().filter(|place| *place < 60).ok_or_else(invalid)?;
+        places.push(place);
+        decimals = fraction;
+    }
+    
+    let mut number = SexagesimalNum::from_parts(negative, integer_part, places.clone());
+    if !decimals.is_empty() {
+        // Append the decimals of the last component, then divide by 10^k
+        let digits = decimals.parse::<u64>().ok().filter(|_| decimals.len() <= 18).ok_or_else(invalid)?;
+        let scale = 10i64.pow(decimals.len() as u32);
+        let digits = if negative { -BigInt::from(digits) } else { BigInt::from(digits) };
+        let scaled = number.to_scaled(places.len()) * scale + digits;
+        number = SexagesimalNum::from_scaled(&scaled, places.len())
+            .and_then(|sex| sex.checked_div(
+                &SexagesimalNum::from_integer(scale),
+                places.len() + decimals.len(),
+                Rounding::HalfEven,
+            ))
+            .ok_or_else(invalid)?;
+    }
+    
+    let unit = metrology::find_unit(unit).ok_or_else(invalid)?;
+    Ok(Value::Quantity(Quantity::new(Value::Sexagesimal(number), unit)))
+}
+
+/// ATF transliteration of base-60 places, with a ';' before the
+/// fractional places and 0(diš) for an empty place
+pub fn format_transliteration(negative: bool, integer_places: &[u8], fractional_places: &[u8]) -> String {
+    let signs = |places: &[u8]| places.iter()
+        .map(|place| match (place / 10, place % 10) {
+            (0, units) => format!("{}(diš)", units),
+            (tens, 0) => format!("{}(u)", tens),
+            (tens, units) => format!("{}(u) {}(diš)", tens, units),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    
+    let sign = if negative { "-" } else { "" };
+    if fractional_places.is_empty() {
+        format!("{}{}", sign, signs(integer_places))
+    } else {
+        format!("{}{} ; {}", sign, signs(integer_places), signs(fractional_places))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,6 +900,16 @@ mod tests {
         assert_eq!(parse_number("-42").unwrap(), Value::Integer(-42));
     }
 
+    #[test]
+    fn test_parse_big_integer() {
+        let result = parse_number("9223372036854775808").unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(i64::MAX) + 1));
+        assert_eq!(format!("{}", result), "9223372036854775808");
+        
+        // Values that fit are narrowed back to Integer
+        assert_eq!(Value::from_bigint(BigInt::from(7)), Value::Integer(7));
+    }
+
     #[test]
     fn test_parse_base10_float() {
         assert_eq!(parse_number("123.45").unwrap(), Value::Float(123.45));
@@ -210,7 +923,7 @@ mod tests {
         let result = parse_number("1;30").unwrap();
         if let Value::Sexagesimal(sex) = result {
             assert_eq!(sex.integer_part, 1);
-            assert_eq!(sex.fractional_part, 30);
+            assert_eq!(sex.fractional_places, vec![30]);
             assert_eq!(sex.to_f64(), 1.5);
         } else {
             panic!("Expected Sexagesimal value");
@@ -235,13 +948,13 @@ mod tests {
 
     #[test]
     fn test_sexagesimal_from_float() {
-        let sex = SexagesimalNum::from_f64(2.25);
+        let sex = SexagesimalNum::from_f64_rounded(2.25, 1, Rounding::HalfEven).unwrap();
         assert_eq!(sex.integer_part, 2);
-        assert_eq!(sex.fractional_part, 15); // 0.25 * 60 = 15
+        assert_eq!(sex.fractional_places, vec![15]); // 0.25 * 60 = 15
         
-        let sex = SexagesimalNum::from_f64(3.5);
+        let sex = SexagesimalNum::from_f64_rounded(3.5, 1, Rounding::HalfEven).unwrap();
         assert_eq!(sex.integer_part, 3);
-        assert_eq!(sex.fractional_part, 30); // 0.5 * 60 = 30
+        assert_eq!(sex.fractional_places, vec![30]); // 0.5 * 60 = 30
     }
 
     #[test]
@@ -251,4 +964,201 @@ mod tests {
         assert!(parse_number("1;60").is_err()); // Fractional part too large
         assert!(parse_number("abc").is_err());
     }
+    
+    #[test]
+    fn test_sexagesimal_checked_pow() {
+        let num = SexagesimalNum::new(1, 30).unwrap();
+        assert_eq!(num.checked_pow(2), Some(SexagesimalNum::new(2, 15).unwrap()));
+        assert_eq!(num.checked_pow(0), Some(SexagesimalNum::new(1, 0).unwrap()));
+        
+        // 0;01 squared needs two fractional places
+        let num = SexagesimalNum::new(0, 1).unwrap();
+        assert_eq!(num.checked_pow(2), Some(SexagesimalNum::from_parts(false, 0, vec![0, 1])));
+    }
+    
+    #[test]
+    fn test_multi_place_sexagesimal() {
+        // sqrt(2) as on YBC 7289
+        let result = parse_number("1;24,51,10").unwrap();
+        let expected = SexagesimalNum::from_parts(false, 1, vec![24, 51, 10]);
+        assert_eq!(result, Value::Sexagesimal(expected.clone()));
+        assert_eq!(format!("{}", expected), "1;24,51,10");
+        assert!((expected.to_f64() - 2f64.sqrt()).abs() < 1e-6);
+        
+        // Trailing zero places are dropped
+        let result = parse_number("2;30,00").unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::new(2, 30).unwrap()));
+        
+        assert!(parse_number("1;30,60").is_err());
+    }
+    
+    #[test]
+    fn test_sexagesimal_exact_arithmetic() {
+        let a = SexagesimalNum::new(1, 30).unwrap();
+        let b = SexagesimalNum::from_parts(false, 0, vec![0, 30]);
+        assert_eq!(a.checked_add(&b), Some(SexagesimalNum::from_parts(false, 1, vec![30, 30])));
+        assert_eq!(a.checked_mul(&b), Some(SexagesimalNum::from_parts(false, 0, vec![0, 45])));
+        
+        // 1 / 1;30 = 0;40 exactly
+        let one = SexagesimalNum::from_integer(1);
+        assert_eq!(one.checked_div(&a, 4, Rounding::HalfEven), Some(SexagesimalNum::new(0, 40).unwrap()));
+        assert_eq!(one.checked_div(&SexagesimalNum::from_integer(0), 4, Rounding::HalfEven), None);
+        
+        let (quotient, remainder) = SexagesimalNum::new(-2, 30).unwrap()
+            .div_rem_floor(&SexagesimalNum::from_integer(1))
+            .unwrap();
+        assert_eq!(quotient, SexagesimalNum::from_integer(-3));
+        assert_eq!(remainder, SexagesimalNum::new(0, 30).unwrap());
+    }
+    
+    #[test]
+    fn test_sexagesimal_rounding() {
+        // 1/7 = 0;08,34,17,08,34,...
+        let seventh = SexagesimalNum::from_integer(1);
+        let seven = SexagesimalNum::from_integer(7);
+        let rounded = |places, rounding| seventh.checked_div(&seven, places, rounding).unwrap();
+        
+        assert_eq!(format!("{}", rounded(3, Rounding::Truncate)), "0;08,34,17");
+        assert_eq!(format!("{}", rounded(2, Rounding::HalfEven)), "0;08,34");
+        assert_eq!(format!("{}", rounded(3, Rounding::HalfEven)), "0;08,34,17");
+        assert_eq!(format!("{}", rounded(1, Rounding::HalfEven)), "0;09");
+        
+        // Floor and truncate differ below zero
+        let negative = SexagesimalNum::from_parts(true, 0, vec![8, 34, 17]);
+        assert_eq!(format!("{}", negative.round_to(1, Rounding::Truncate).unwrap()), "-0;08");
+        assert_eq!(format!("{}", negative.round_to(1, Rounding::Floor).unwrap()), "-0;09");
+        
+        // Ties go to the even place
+        let tie = SexagesimalNum::from_parts(false, 0, vec![1, 30]);
+        assert_eq!(format!("{}", tie.round_to(1, Rounding::HalfEven).unwrap()), "0;02");
+        let tie = SexagesimalNum::from_parts(false, 0, vec![2, 30]);
+        assert_eq!(format!("{}", tie.round_to(1, Rounding::HalfEven).unwrap()), "0;02");
+    }
+    
+    #[test]
+    fn test_negative_sexagesimal() {
+        let result = parse_number("-1;30").unwrap();
+        let expected = SexagesimalNum::from_parts(true, 1, vec![30]);
+        assert_eq!(result, Value::Sexagesimal(expected.clone()));
+        assert_eq!(expected.to_f64(), -1.5);
+        assert_eq!(expected.to_scaled(1), BigInt::from(-90));
+        assert_eq!(format!("{}", expected), "-1;30");
+        
+        // The sign belongs to the whole number, not just the integer part
+        let result = parse_number("-0;30").unwrap();
+        assert_eq!(result, Value::Sexagesimal(SexagesimalNum::from_parts(true, 0, vec![30])));
+        assert_eq!(format!("{}", result), "-0;30");
+        assert_eq!(parse_number("-0,30").unwrap(), Value::Float(-0.5));
+        
+        assert!(parse_number("1;-30").is_err());
+    }
+    
+    #[test]
+    fn test_negative_sexagesimal_round_trip() {
+        let num = SexagesimalNum::new(-1, 30).unwrap();
+        assert_eq!(format!("{}", SexagesimalNum::from_f64_rounded(num.to_f64(), 1, Rounding::HalfEven).unwrap()), "-1;30");
+        assert_eq!(SexagesimalNum::from_scaled(&num.to_scaled(1), 1), Some(num.clone()));
+        assert_eq!(num.negated(), SexagesimalNum::new(1, 30).unwrap());
+        
+        // Negative zero collapses to zero
+        assert_eq!(SexagesimalNum::from_parts(true, 0, vec![0]), SexagesimalNum::new(0, 0).unwrap());
+        assert_eq!(format!("{}", SexagesimalNum::from_f64_rounded(-0.001, 1, Rounding::HalfEven).unwrap()), "0");
+    }
+    
+    #[test]
+    fn test_exactness() {
+        assert!(parse_number("1;30").unwrap().is_exact());
+        assert!(parse_number("42").unwrap().is_exact());
+        assert!(!parse_number("1.5").unwrap().is_exact());
+        assert!(!SexagesimalNum::from_f64_rounded(1.5, 1, Rounding::HalfEven).unwrap().exact);
+        
+        // Rounding only loses exactness when it drops a non-zero place
+        let num = SexagesimalNum::from_parts(false, 1, vec![30, 20]);
+        assert!(num.round_to(2, Rounding::HalfEven).unwrap().exact);
+        assert!(!num.round_to(1, Rounding::HalfEven).unwrap().exact);
+        
+        // Exact division stays exact, 1/7 does not
+        let one = SexagesimalNum::from_integer(1);
+        assert!(one.checked_div(&SexagesimalNum::from_integer(8), 4, Rounding::HalfEven).unwrap().exact);
+        assert!(!one.checked_div(&SexagesimalNum::from_integer(7), 4, Rounding::HalfEven).unwrap().exact);
+        
+        // Inexactness is contagious
+        let rough = SexagesimalNum::from_f64_rounded(0.5, 1, Rounding::HalfEven).unwrap();
+        assert!(!one.checked_add(&rough).unwrap().exact);
+        
+        assert_eq!(format!("{}", Value::Sexagesimal(rough)), "≈0;30");
+        assert_eq!(format!("{}", Value::Float(1.5)), "≈1.5");
+        assert_eq!(format!("{}", Value::Integer(3)), "3");
+    }
+    
+    #[test]
+    fn test_cuneiform_display() {
+        let sex = SexagesimalNum::from_parts(false, 1, vec![30]);
+        assert_eq!(format!("{:#}", sex), "𒁹;𒌍");
+        assert_eq!(format!("{:#}", Value::Integer(-3661)), "-𒁹 𒁹 𒁹");
+        assert_eq!(format!("{:#}", Value::Float(0.5)), "≈𒑲;𒌍");
+        assert_eq!(Value::Integer(90).to_cuneiform(",", 4), "𒁹,𒌍");
+        assert_eq!(format!("{:#}", Value::Boolean(true)), "true");
+    }
+    
+    #[test]
+    fn test_parse_transliteration() {
+        assert_eq!(parse_number("3(u) 4(diš)").unwrap(), Value::Integer(34));
+        assert_eq!(parse_number("1(diš) 2(u) 3(diš)").unwrap(), Value::Integer(83));
+        assert_eq!(parse_number("1(diš) 3(u)").unwrap(), Value::Integer(90));
+        assert_eq!(parse_number("2(diš) 0(diš) 5(diš)").unwrap(), Value::Integer(7205));
+        assert_eq!(parse_number("1(geš₂) 3(u)").unwrap(), Value::Integer(90));
+        assert_eq!(parse_number("1(szar2) 1(gesz2)").unwrap(), Value::Integer(3660));
+        assert_eq!(
+            parse_number("1(diš) 1/2(diš)").unwrap(),
+            Value::Sexagesimal(SexagesimalNum::from_parts(false, 1, vec![30]))
+        );
+        assert_eq!(
+            parse_number("-1(diš) ; 2(u) 4(diš) 5(u) 1(diš) 1(u)").unwrap(),
+            Value::Sexagesimal(SexagesimalNum::from_parts(true, 1, vec![24, 51, 10]))
+        );
+    }
+    
+    #[test]
+    fn test_invalid_transliteration() {
+        assert!(parse_number("7(u)").is_err());
+        assert!(parse_number("1(aš)").is_err());
+        assert!(parse_number("1/7(diš)").is_err());
+        assert!(parse_number("2/3(u)").is_err());
+        assert!(parse_number("1(diš) ; 1(šar₂)").is_err());
+    }
+    
+    #[test]
+    fn test_transliteration_output() {
+        assert_eq!(Value::Integer(83).to_transliteration(4), "1(diš) 2(u) 3(diš)");
+        assert_eq!(Value::Integer(3600).to_transliteration(4), "1(diš) 0(diš) 0(diš)");
+        let sex = SexagesimalNum::from_parts(true, 1, vec![24, 51, 10]);
+        let text = Value::Sexagesimal(sex.clone()).to_transliteration(4);
+        assert_eq!(text, "-1(diš) ; 2(u) 4(diš) 5(u) 1(diš) 1(u)");
+        assert_eq!(parse_number(&text).unwrap(), Value::Sexagesimal(sex));
+        assert_eq!(Value::Float(0.5).to_transliteration(4), "≈0(diš) ; 3(u)");
+    }
+    
+    #[test]
+    fn test_parse_angles_and_times() {
+        let angle = parse_number("12°30'15\"").unwrap();
+        let Value::Quantity(quantity) = &angle else { panic!("expected a quantity") };
+        assert_eq!(*quantity.amount, Value::Sexagesimal(SexagesimalNum::from_parts(false, 12, vec![30, 15])));
+        assert_eq!(angle.to_string(), "12°30'15\"");
+        
+        assert_eq!(parse_number("12°").unwrap().to_string(), "12°");
+        assert_eq!(parse_number("-5°07'").unwrap().to_string(), "-5°07'");
+        assert_eq!(parse_number("0°00'15.5\"").unwrap().to_string(), "0°00'15.5\"");
+        assert_eq!(parse_number("12°15\"").unwrap().to_string(), "12°00'15\"");
+        assert!(parse_number("12°30").is_err());
+        assert!(parse_number("12°30'15").is_err());
+        
+        let time = parse_number("1:02:03.5").unwrap();
+        let Value::Quantity(quantity) = &time else { panic!("expected a quantity") };
+        assert_eq!(*quantity.amount, Value::Sexagesimal(SexagesimalNum::from_parts(false, 1, vec![2, 3, 30])));
+        assert_eq!(time.to_string(), "1:02:03.5");
+        
+        assert!(parse_number("1:60:00").is_err());
+        assert!(parse_number("1:02.5:00").is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+use abzu_interpreter::ast::{Expression, Program, Statement};
+use abzu_interpreter::{Environment, Interpreter, Value};
+
+#[test]
+fn test_host_reads_map() {
+    let mut env = Environment::new();
+    let interpreter = Interpreter::new();
+    let number = |s: &str| Expression::Number(s.to_string());
+    let row = Expression::Map(vec![
+        ("line".to_string(), number("3")),
+        ("width".to_string(), number("1;59")),
+    ]);
+    
+    let program = Program { statements: vec![Statement::Expression(row)] };
+    
+    let Some(Value::Map(entries)) = interpreter.eval_program(&program, &mut env).unwrap() else {
+        panic!("expected a map");
+    };
+    assert_eq!(entries[0], ("line".to_string(), Value::Integer(3)));
+    assert_eq!(entries[1].0, "width");
+    assert_eq!(entries[1].1.to_string(), "1;59");
+}
\ No newline at end of file